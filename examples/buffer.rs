@@ -0,0 +1,96 @@
+use sc_client::{
+    Buffer, BufferGen, BufferGenFlags, BufferReadOptions, BufferWriteOptions, DumpOscMode, Options,
+    ScClientResult, Server,
+};
+use std::env;
+
+fn main() -> ScClientResult<()> {
+    env::set_var("RUST_LOG", "sc_client=debug");
+    env_logger::init();
+
+    let options = Options::new("examples/settings.toml");
+    let server = Server::new(options)?;
+    server.boot()?;
+    server.sync()?;
+
+    server.set_dump_osc_mode(DumpOscMode::PrintParsed)?;
+    server.sync()?;
+
+    let path = env::temp_dir().join("sc_client_buffer.wav");
+    let path = path.to_str().unwrap();
+
+    let buffer_number = server.allocate_buffer_numbers(3)?;
+    let buffer = Buffer::alloc(&server, buffer_number, 44100, 2, |number| {
+        println!("buffer {} is allocated", number);
+    })?;
+    server.sync()?;
+
+    let sine: Vec<f32> = (0..44100)
+        .map(|i| (i as f32 / 100.0 * std::f32::consts::PI * 2.0).sin())
+        .collect();
+    buffer
+        .set_range(0, &sine)?
+        .fill(0, 10, 0.0)?
+        .set(&[(0, 1.0)])?;
+    server.sync()?;
+
+    buffer.get(&[0, 10, 25], |values| println!("samples: {:?}", values))?;
+    buffer.get_range(0, 44100, |values| {
+        println!("received {} samples", values.len())
+    })?;
+    server.sync()?;
+
+    buffer.write(path, &BufferWriteOptions::default(), |number| {
+        println!("buffer {} is written", number);
+    })?;
+    server.sync()?;
+
+    let file_buffer = Buffer::alloc_read(&server, buffer_number + 1, path, 0, -1, |number| {
+        println!("file is read into buffer {}", number);
+    })?;
+    server.sync()?;
+
+    file_buffer.get_info(|info| {
+        println!(
+            "buffer {} has {} frames, {} channels at {} Hz",
+            info.number, info.num_of_frames, info.num_of_channels, info.sample_rate
+        );
+    })?;
+    Buffer::query(&server, &[buffer_number, buffer_number + 1], |infos| {
+        println!("{:?}", infos)
+    })?;
+    server.sync()?;
+
+    let read_options = BufferReadOptions {
+        num_of_frames: 22050,
+        ..BufferReadOptions::default()
+    };
+    buffer
+        .zero(|_| {})?
+        .read_channel(path, &read_options, &[1], |number| {
+            println!("the second channel is read into buffer {}", number);
+        })?;
+    server.sync()?;
+
+    let wavetable = Buffer::alloc(&server, buffer_number + 2, 1024, 1, |_| {})?;
+    server.sync()?;
+    wavetable.generate(
+        &BufferGen::Sine1 {
+            flags: BufferGenFlags {
+                normalize: true,
+                wavetable: true,
+                clear: true,
+            },
+            amplitudes: vec![1.0, 0.5, 0.25],
+        },
+        |number| println!("wavetable is generated in buffer {}", number),
+    )?;
+    server.sync()?;
+
+    buffer.free(|_| {})?;
+    wavetable.free(|_| {})?;
+    file_buffer.free(|_| {})?;
+    server.sync()?;
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use sc_client::{
+    AddAction, ControlBus, DumpOscMode, Node, Options, ScClientResult, Server, Synth,
+    SynthDefinition,
+};
+use std::env;
+
+fn main() -> ScClientResult<()> {
+    env::set_var("RUST_LOG", "sc_client=debug");
+    env_logger::init();
+
+    let options = Options::new("examples/settings.toml");
+    let server = Server::new(options)?;
+    server.boot()?;
+    server.sync()?;
+
+    server.set_dump_osc_mode(DumpOscMode::PrintParsed)?;
+    server.sync()?;
+
+    let path_to_synthdef = "examples/synthdefs/sc_client_test_1.scsyndef";
+    let synth_name = "sc_client_test_1";
+
+    SynthDefinition::load(&server, path_to_synthdef)?;
+    server.sync()?;
+
+    let bus = ControlBus::alloc(&server, 2)?;
+    bus.set_range(0, &[0.1, 440.0])?;
+    server.sync()?;
+
+    bus.get_all(|values| println!("bus values: {:?}", values))?;
+    server.sync()?;
+
+    let synth = Synth::new(&server, synth_name, &AddAction::Tail, 0, &vec![])?;
+    synth.map_range_to_control_bus(&[("amp".into(), bus.get_index(), 1)])?;
+    synth.map_to_control_bus(&[("freq".into(), (bus.get_index() + 1).into()).into()])?;
+    rest(2);
+
+    bus.set(&[(1, 660.0)])?;
+    rest(2);
+
+    bus.fill(0.0)?;
+    bus.get(&[0, 1], |values| println!("bus values: {:?}", values))?;
+    server.sync()?;
+
+    synth.free()?;
+    bus.free()?;
+    SynthDefinition::free(&server, synth_name)?;
+    server.sync()?;
+
+    Ok(())
+}
+
+fn rest(secs: u64) {
+    std::thread::sleep(std::time::Duration::from_secs(secs));
+}
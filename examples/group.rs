@@ -0,0 +1,71 @@
+use sc_client::{
+    AddAction, DumpOscMode, Group, Options, ScClientResult, Server, Synth, SynthDefinition,
+};
+use std::env;
+
+fn main() -> ScClientResult<()> {
+    env::set_var("RUST_LOG", "sc_client=debug");
+    env_logger::init();
+
+    let options = Options::new("examples/settings.toml");
+    let server = Server::new(options)?;
+    server.boot()?;
+    server.sync()?;
+
+    server.set_dump_osc_mode(DumpOscMode::PrintParsed)?;
+    server.sync()?;
+
+    server.set_receive_notifications(true)?;
+    server
+        .node_watcher()
+        .subscribe_all(|event| println!("node event: {:?}", event));
+    server.sync()?;
+
+    let path_to_synthdef = "examples/synthdefs/sc_client_test_1.scsyndef";
+    let synth_name = "sc_client_test_1";
+
+    SynthDefinition::load(&server, path_to_synthdef)?;
+    server.sync()?;
+
+    let sources = Group::new(&server, &AddAction::Head, 0)?;
+    let effects = Group::new(&server, &AddAction::After, sources.get_id())?;
+    server.sync()?;
+
+    let synth = Synth::new(
+        &server,
+        synth_name,
+        &AddAction::Tail,
+        sources.get_id(),
+        &vec![("amp".into(), 0.1f32.into()).into()],
+    )?;
+    Synth::new(
+        &server,
+        synth_name,
+        &AddAction::Tail,
+        sources.get_id(),
+        &vec![("freq".into(), 660.0f32.into()).into()],
+    )?;
+    rest(2);
+
+    sources.move_node_to_head(synth.get_id())?;
+    server.sync()?;
+
+    server.query_tree(0, true, |tree| println!("{:#?}", tree))?;
+    server.sync()?;
+
+    let parallel = Group::new_parallel(&server, &AddAction::Tail, effects.get_id())?;
+    server.sync()?;
+
+    parallel.free_all()?;
+    sources.deep_free()?;
+    server.sync()?;
+
+    SynthDefinition::free(&server, synth_name)?;
+    server.sync()?;
+
+    Ok(())
+}
+
+fn rest(secs: u64) {
+    std::thread::sleep(std::time::Duration::from_secs(secs));
+}
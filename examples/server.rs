@@ -6,7 +6,7 @@ fn main() -> ScClientResult<()> {
     env_logger::init();
 
     let options = Options::new("examples/settings.toml");
-    let server = Server::new(options);
+    let server = Server::new(options)?;
     server.boot()?;
 
     server.sync()?;
@@ -38,6 +38,11 @@ fn main() -> ScClientResult<()> {
     server.sync()?;
 
     server.set_receive_notifications(true)?;
+    println!(
+        "Client ID: {:?}, max logins: {:?}",
+        server.get_client_id(),
+        server.get_max_logins()
+    );
 
     server.sync()?;
 
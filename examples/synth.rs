@@ -1,5 +1,5 @@
 use sc_client::{
-    AddAction, DumpOscMode, Options, ScClientResult, Server, Synth, SynthDefinition,
+    AddAction, DumpOscMode, Node, Options, ScClientResult, Server, Synth, SynthDefinition,
 };
 use std::env;
 
@@ -8,7 +8,7 @@ fn main() -> ScClientResult<()> {
     env_logger::init();
 
     let options = Options::new("examples/settings.toml");
-    let server = Server::new(options);
+    let server = Server::new(options)?;
     server.boot()?;
     server.sync()?;
 
@@ -59,6 +59,35 @@ fn main() -> ScClientResult<()> {
 
     server.sync()?;
 
+    synth.set(&[("freq".into(), 220.0f32.into()).into()])?;
+    rest(1);
+
+    synth.run(false)?;
+    rest(1);
+
+    synth.run(true)?;
+    synth_2.fill(&[("amp".into(), 1, 0.05f32.into())])?;
+    rest(1);
+
+    synth.free()?;
+    synth_2.free()?;
+    server.sync()?;
+
+    // the synth starts with the new frequency, because both commands are in one bundle
+    let mut bundled_synth = None;
+    server.make_bundle(None, |_| {
+        let synth = Synth::new(&server, synth_name, &AddAction::Tail, 0, &vec![])?;
+        synth.set(&[("freq".into(), 330.0f32.into()).into()])?;
+        bundled_synth = Some(synth);
+        Ok(())
+    })?;
+    rest(1);
+
+    if let Some(synth) = bundled_synth {
+        synth.free()?;
+    }
+    server.sync()?;
+
     SynthDefinition::free(&server, synth_name)?;
     server.sync()?;
 
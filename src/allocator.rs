@@ -0,0 +1,173 @@
+#[cfg(test)]
+mod tests;
+use crate::{ScClientError, ScClientResult};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+/// Allocates contiguous blocks of indices (bus channels or buffer numbers) in the range
+/// `[offset, offset + size)`.
+///
+/// The first block, which is large enough, is used. Adjacent free blocks are merged on free,
+/// so the range doesn't fragment after freeing.
+#[derive(Clone, Debug)]
+pub struct BlockAllocator {
+    offset: i32,
+    size: i32,
+    /// start index -> size of the free blocks.
+    free_blocks: BTreeMap<i32, i32>,
+    /// start index -> size of the allocated blocks.
+    allocated_blocks: BTreeMap<i32, i32>,
+}
+
+impl BlockAllocator {
+    pub fn new(offset: i32, size: i32) -> Self {
+        let mut allocator = BlockAllocator {
+            offset,
+            size,
+            free_blocks: BTreeMap::new(),
+            allocated_blocks: BTreeMap::new(),
+        };
+        allocator.reset();
+        allocator
+    }
+
+    /// Allocates `size` sequential indices and returns the first one.
+    pub fn alloc(&mut self, size: i32) -> ScClientResult<i32> {
+        if size < 1 {
+            return Err(ScClientError::InvalidBlockSize(size));
+        }
+
+        let (start, block_size) = self
+            .free_blocks
+            .iter()
+            .find(|(_, &block_size)| block_size >= size)
+            .map(|(&start, &block_size)| (start, block_size))
+            .ok_or(ScClientError::NotEnoughSpace(size))?;
+
+        self.free_blocks.remove(&start);
+        if block_size > size {
+            self.free_blocks.insert(start + size, block_size - size);
+        }
+        self.allocated_blocks.insert(start, size);
+        Ok(start)
+    }
+
+    /// Frees the block, which starts at `start`.
+    pub fn free(&mut self, start: i32) -> ScClientResult<()> {
+        let mut size = self
+            .allocated_blocks
+            .remove(&start)
+            .ok_or(ScClientError::NotAllocated(start))?;
+        let mut start = start;
+
+        let next_start = start + size;
+        if let Some(next_size) = self.free_blocks.remove(&next_start) {
+            size += next_size;
+        }
+
+        let previous_block = self
+            .free_blocks
+            .range(..start)
+            .next_back()
+            .map(|(&previous_start, &previous_size)| (previous_start, previous_size));
+        if let Some((previous_start, previous_size)) = previous_block {
+            if previous_start + previous_size == start {
+                self.free_blocks.remove(&previous_start);
+                start = previous_start;
+                size += previous_size;
+            }
+        }
+
+        self.free_blocks.insert(start, size);
+        Ok(())
+    }
+
+    /// Frees all the blocks.
+    pub fn reset(&mut self) {
+        self.allocated_blocks.clear();
+        self.free_blocks.clear();
+        if self.size > 0 {
+            self.free_blocks.insert(self.offset, self.size);
+        }
+    }
+
+    pub fn get_offset(&self) -> i32 {
+        self.offset
+    }
+
+    pub fn get_size(&self) -> i32 {
+        self.size
+    }
+
+    /// Returns the number of indices, which are not allocated.
+    pub fn get_num_of_free(&self) -> i32 {
+        self.free_blocks.values().sum()
+    }
+}
+
+/// Allocates node IDs in the range of the client, the same way as sclang does.
+///
+/// The upper bits of an ID are the client ID, so clients, which share the server, never
+/// collide. The lower 26 bits are the node number, which starts at 1000. Freed IDs are reused
+/// before the new ones.
+#[derive(Clone, Debug)]
+pub struct NodeIdAllocator {
+    client_id: i32,
+    next_number: i32,
+    freed_ids: VecDeque<i32>,
+    freed_ids_set: HashSet<i32>,
+}
+
+impl NodeIdAllocator {
+    const INITIAL_NUMBER: i32 = 1000;
+    const MAX_NUMBER: i32 = 0x03FF_FFFF;
+
+    pub fn new(client_id: i32) -> Self {
+        NodeIdAllocator {
+            client_id,
+            next_number: NodeIdAllocator::INITIAL_NUMBER,
+            freed_ids: VecDeque::new(),
+            freed_ids_set: HashSet::new(),
+        }
+    }
+
+    pub fn alloc(&mut self) -> i32 {
+        if let Some(id) = self.freed_ids.pop_front() {
+            self.freed_ids_set.remove(&id);
+            return id;
+        }
+
+        let number = self.next_number;
+        self.next_number = if number >= NodeIdAllocator::MAX_NUMBER {
+            NodeIdAllocator::INITIAL_NUMBER
+        } else {
+            number + 1
+        };
+        number | self.get_mask()
+    }
+
+    /// Returns the ID for reuse. IDs of the other clients are ignored.
+    pub fn free(&mut self, id: i32) {
+        let number = id & NodeIdAllocator::MAX_NUMBER;
+        if id & !NodeIdAllocator::MAX_NUMBER != self.get_mask()
+            || number < NodeIdAllocator::INITIAL_NUMBER
+            || self.freed_ids_set.contains(&id)
+        {
+            return;
+        }
+        self.freed_ids.push_back(id);
+        self.freed_ids_set.insert(id);
+    }
+
+    /// Starts allocating from the beginning of the client's range and forgets the freed IDs.
+    pub fn reset(&mut self, client_id: i32) {
+        *self = NodeIdAllocator::new(client_id);
+    }
+
+    pub fn get_client_id(&self) -> i32 {
+        self.client_id
+    }
+
+    fn get_mask(&self) -> i32 {
+        self.client_id << 26
+    }
+}
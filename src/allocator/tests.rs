@@ -0,0 +1,81 @@
+use super::*;
+
+#[test]
+fn alloc_sequential_blocks() {
+    let mut allocator = BlockAllocator::new(16, 8);
+    assert_eq!(16, allocator.alloc(2).unwrap());
+    assert_eq!(18, allocator.alloc(4).unwrap());
+    assert_eq!(22, allocator.alloc(2).unwrap());
+    assert!(allocator.alloc(1).is_err());
+    assert!(allocator.alloc(0).is_err());
+    assert_eq!(0, allocator.get_num_of_free());
+}
+
+#[test]
+fn reuse_freed_blocks() {
+    let mut allocator = BlockAllocator::new(0, 8);
+    let first = allocator.alloc(2).unwrap();
+    let second = allocator.alloc(2).unwrap();
+    allocator.alloc(4).unwrap();
+
+    allocator.free(first).unwrap();
+    assert!(allocator.free(first).is_err());
+    assert!(allocator.alloc(3).is_err());
+    assert_eq!(first, allocator.alloc(1).unwrap());
+    assert_eq!(1, allocator.alloc(1).unwrap());
+
+    allocator.free(second).unwrap();
+    assert_eq!(second, allocator.alloc(2).unwrap());
+}
+
+#[test]
+fn merge_adjacent_free_blocks() {
+    let mut allocator = BlockAllocator::new(0, 6);
+    let blocks: Vec<i32> = (0..3).map(|_| allocator.alloc(2).unwrap()).collect();
+
+    allocator.free(blocks[0]).unwrap();
+    allocator.free(blocks[2]).unwrap();
+    assert!(allocator.alloc(4).is_err());
+
+    // freeing the middle block merges it with both neighbours
+    allocator.free(blocks[1]).unwrap();
+    assert_eq!(0, allocator.alloc(6).unwrap());
+}
+
+#[test]
+fn reset_frees_everything() {
+    let mut allocator = BlockAllocator::new(4, 4);
+    allocator.alloc(3).unwrap();
+    allocator.reset();
+    assert_eq!(4, allocator.get_num_of_free());
+    assert_eq!(4, allocator.alloc(4).unwrap());
+}
+
+#[test]
+fn alloc_node_ids_in_client_range() {
+    let mut allocator = NodeIdAllocator::new(0);
+    assert_eq!(1000, allocator.alloc());
+    assert_eq!(1001, allocator.alloc());
+
+    allocator.reset(2);
+    assert_eq!((2 << 26) + 1000, allocator.alloc());
+    assert_eq!((2 << 26) + 1001, allocator.alloc());
+}
+
+#[test]
+fn reuse_freed_node_ids() {
+    let mut allocator = NodeIdAllocator::new(1);
+    let first = allocator.alloc();
+    let second = allocator.alloc();
+
+    allocator.free(second);
+    allocator.free(second);
+    allocator.free(first);
+    // IDs of other clients and permanent IDs are ignored
+    allocator.free(1000);
+    allocator.free((1 << 26) + 1);
+
+    assert_eq!(second, allocator.alloc());
+    assert_eq!(first, allocator.alloc());
+    assert_eq!((1 << 26) + 1002, allocator.alloc());
+}
@@ -0,0 +1,220 @@
+#[cfg(test)]
+mod tests;
+use crate::buffer::BufferInfoResponder;
+use crate::osc_server::PendingRequest;
+use crate::server::{QueryTreeResponder, StatusResponder, VersionResponder};
+use crate::synth::ControlValueResponder;
+use crate::{
+    types::NodeValue, types::OscType, Buffer, BufferInfo, NodeTree, Options, OscResponder,
+    OscServer, ScClientError, ScClientResult, Server, ServerStatus, ServerVersion, Synth,
+};
+use std::future::Future;
+use std::ops::Deref;
+use tokio::sync::{mpsc, oneshot};
+
+/// [`Server`](struct.Server.html), which returns futures for the commands with replies,
+/// instead of calling back. All the other methods of `Server` are available through `Deref`.
+///
+/// The futures don't borrow the server, so they can be moved to another task. They fail, if
+/// the server doesn't reply in [the sync timeout](struct.Server.html#method.set_sync_timeout),
+/// and need a tokio runtime with the time driver enabled. The requests are synced like the
+/// blocking ones, for example, [`Server::status`](struct.Server.html#method.status), so they
+/// fail with [`ScClientError::ServerFailure`](enum.ScClientError.html), if the server replies
+/// with `/fail`. The responder is removed, when the future is resolved or dropped.
+///
+/// ```no_run
+/// # use sc_client::{AsyncServer, Options, ScClientResult};
+/// # async fn run() -> ScClientResult<()> {
+/// let server = AsyncServer::new(Options::default())?;
+/// server.boot()?;
+/// server.sync().await?;
+/// let status = server.get_status().await?;
+/// println!("{} synths are running", status.num_of_synths);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AsyncServer {
+    server: Server,
+}
+
+impl AsyncServer {
+    /// Creates the handle of the server. See [`Server::new`](struct.Server.html#method.new).
+    pub fn new(options: Options) -> ScClientResult<Self> {
+        Ok(AsyncServer {
+            server: Server::new(options)?,
+        })
+    }
+
+    pub fn into_inner(self) -> Server {
+        self.server
+    }
+
+    /// Resolves, when the server has processed all the previous commands. Fails with
+    /// [`ScClientError::SyncTimeout`](enum.ScClientError.html), if the server hasn't replied
+    /// in [the sync timeout](struct.Server.html#method.set_sync_timeout).
+    pub fn sync(&self) -> impl Future<Output = ScClientResult<()>> + Send + 'static {
+        let (sender, receiver) = oneshot::channel();
+        let timeout = self.server.get_sync_timeout();
+        let mut sync = PendingRequest::new(self.server.osc_server.clone());
+        let sent = sync.send_with_sync(
+            |_| Ok(()),
+            move |failures| {
+                let _ = sender.send(failures);
+            },
+        );
+
+        async move {
+            // the sync is cancelled, when the future is resolved or dropped
+            sent?;
+            let _sync = sync;
+            let result = tokio::time::timeout(timeout, receiver).await;
+            match result {
+                Ok(Ok(failures)) => match failures.into_iter().next() {
+                    Some(failure) => Err(ScClientError::ServerFailure(failure)),
+                    None => Ok(()),
+                },
+                Ok(Err(_)) => Err(ScClientError::NoReply),
+                Err(_) => Err(ScClientError::SyncTimeout(timeout)),
+            }
+        }
+    }
+
+    /// Resolves with the status of the server.
+    /// > status won't return, if the server is in dump_osc mode
+    pub fn get_status(
+        &self,
+    ) -> impl Future<Output = ScClientResult<ServerStatus>> + Send + 'static {
+        self.request("/status", StatusResponder::new, |osc_server| {
+            osc_server.send_message("/status", None).map(|_| ())
+        })
+    }
+
+    /// Resolves with the version of the server.
+    pub fn get_version(
+        &self,
+    ) -> impl Future<Output = ScClientResult<ServerVersion>> + Send + 'static {
+        self.request("/version", VersionResponder::new, |osc_server| {
+            osc_server.send_message("/version", None).map(|_| ())
+        })
+    }
+
+    /// Resolves with the tree of nodes, starting at the group with `group_id`. See
+    /// [`Server::query_tree`](struct.Server.html#method.query_tree).
+    pub fn query_tree(
+        &self,
+        group_id: i32,
+        include_controls: bool,
+    ) -> impl Future<Output = ScClientResult<NodeTree>> + Send + 'static {
+        let make_responder = move |on_reply| QueryTreeResponder::new(group_id, on_reply);
+        self.request("/g_queryTree", make_responder, move |osc_server| {
+            let args = vec![group_id.into(), (include_controls as i32).into()];
+            osc_server
+                .send_message("/g_queryTree", Some(args))
+                .map(|_| ())
+        })
+    }
+
+    /// Resolves with the values of the synth's controls. See
+    /// [`Synth::get_control_value`](struct.Synth.html#method.get_control_value).
+    pub fn get_control_value(
+        &self,
+        synth: &Synth,
+        params: &[OscType],
+    ) -> impl Future<Output = ScClientResult<Vec<NodeValue>>> + Send + 'static {
+        let synth_id = synth.get_id();
+        let mut send_args = vec![OscType::Int(synth_id)];
+        send_args.extend(params.iter().cloned());
+        let params = params.to_vec();
+        let make_responder = move |on_reply| ControlValueResponder::new(synth_id, params, on_reply);
+        self.request("/s_get", make_responder, move |osc_server| {
+            osc_server
+                .send_message("/s_get", Some(send_args))
+                .map(|_| ())
+        })
+    }
+
+    /// Resolves with the info of the buffers. See
+    /// [`Buffer::query`](struct.Buffer.html#method.query).
+    pub fn query_buffers(
+        &self,
+        numbers: &[i32],
+    ) -> impl Future<Output = ScClientResult<Vec<BufferInfo>>> + Send + 'static {
+        let messages = Buffer::query_messages(numbers);
+        // the server wouldn't reply to no messages
+        let request = if messages.is_empty() {
+            None
+        } else {
+            let numbers = numbers.to_vec();
+            let make_responder = move |on_reply| BufferInfoResponder::new(numbers, on_reply);
+            Some(self.request("/b_query", make_responder, move |osc_server| {
+                for args in messages {
+                    osc_server.send_message("/b_query", Some(args))?;
+                }
+                Ok(())
+            }))
+        };
+        async move {
+            match request {
+                Some(request) => request.await,
+                None => Ok(Vec::new()),
+            }
+        }
+    }
+
+    /// Sends the request like [`Server::wait_for_reply`](struct.Server.html), but resolves with
+    /// the first reply, instead of blocking.
+    fn request<T, R, M, S>(
+        &self,
+        command: &str,
+        make_responder: M,
+        send: S,
+    ) -> impl Future<Output = ScClientResult<T>> + Send + 'static
+    where
+        T: Send + 'static,
+        R: OscResponder,
+        M: FnOnce(Box<dyn Fn(T) + Send + Sync>) -> R,
+        S: FnOnce(&OscServer) -> ScClientResult<()>,
+    {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let timeout = self.server.get_sync_timeout();
+        let request = self
+            .server
+            .send_request(command, make_responder, send, move |reply| {
+                // the receiver is gone, if the future was dropped
+                let _ = sender.send(reply);
+            });
+
+        async move {
+            // the responder is removed and the sync is cancelled, when the future is resolved
+            // or dropped
+            let _request = request?;
+            let mut value = None;
+            let result = tokio::time::timeout(timeout, async {
+                while let Some(reply) = receiver.recv().await {
+                    if let Some(result) = reply.resolve(&mut value) {
+                        return result;
+                    }
+                }
+                Err(ScClientError::NoReply)
+            })
+            .await;
+            // the reply is enough, if the server hasn't replied to the sync only
+            result.unwrap_or_else(|_| value.ok_or(ScClientError::ReplyTimeout(timeout)))
+        }
+    }
+}
+
+impl Deref for AsyncServer {
+    type Target = Server;
+
+    fn deref(&self) -> &Server {
+        &self.server
+    }
+}
+
+impl From<Server> for AsyncServer {
+    fn from(server: Server) -> Self {
+        AsyncServer { server }
+    }
+}
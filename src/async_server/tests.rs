@@ -0,0 +1,34 @@
+use super::*;
+use crate::osc_server::spawn_tcp_server;
+use std::time::Duration;
+
+/// Connects to a stand-in, which never replies.
+fn silent_server() -> AsyncServer {
+    let address = spawn_tcp_server(1, None, |_| None);
+    let mut options = Options::default();
+    options.udp_port_number = 0;
+    options.tcp_port_number = address.rsplit(':').next().unwrap().parse().unwrap();
+    AsyncServer::new(options).unwrap()
+}
+
+#[tokio::test]
+async fn dropped_sync_is_cancelled() {
+    let server = silent_server();
+    let sync = server.sync();
+    assert!(server.osc_server.has_pending_syncs());
+    drop(sync);
+    assert!(!server.osc_server.has_pending_syncs());
+}
+
+#[tokio::test]
+async fn request_times_out_and_removes_responder() {
+    let server = silent_server();
+    let timeout = Duration::from_millis(100);
+    server.set_sync_timeout(timeout);
+    let num_of_responders = server.osc_server.num_of_responders();
+
+    let error = server.get_status().await.err().unwrap();
+    assert!(matches!(error, ScClientError::ReplyTimeout(t) if t == timeout));
+    assert_eq!(num_of_responders, server.osc_server.num_of_responders());
+    assert!(!server.osc_server.has_pending_syncs());
+}
@@ -0,0 +1,461 @@
+mod buffer_done_responder;
+mod buffer_gen;
+mod buffer_info_responder;
+mod buffer_values_responder;
+#[cfg(test)]
+mod tests;
+use self::buffer_done_responder::BufferDoneResponder;
+pub use self::buffer_gen::{BufferGen, BufferGenFlags};
+pub(crate) use self::buffer_info_responder::BufferInfoResponder;
+use self::buffer_values_responder::BufferValuesResponder;
+use crate::value_request::{checked_range, index_messages, range_messages, request_values};
+use crate::{types::OscType, OscServer, ScClientResult, Server};
+
+/// Server-side sample buffer.
+///
+/// All the commands are asynchronous. The `on_done` callback is called with the buffer number,
+/// when the server has completed the command.
+///
+/// Sample data is sent and requested in chunks, so that every message fits into a UDP packet.
+#[derive(Clone)]
+pub struct Buffer {
+    number: i32,
+    server: Server,
+}
+
+impl Buffer {
+    /// Wraps a buffer, which already exists on the server, without sending any command.
+    pub fn new(server: &Server, number: i32) -> Self {
+        Buffer {
+            number,
+            server: server.clone(),
+        }
+    }
+
+    /// Allocates a buffer with `/b_alloc`. The samples are zeroed.
+    pub fn alloc<F>(
+        server: &Server,
+        number: i32,
+        num_of_frames: i32,
+        num_of_channels: i32,
+        on_done: F,
+    ) -> ScClientResult<Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        let buffer = Buffer::new(server, number);
+        buffer.send_command(
+            "/b_alloc",
+            vec![num_of_frames.into(), num_of_channels.into()],
+            on_done,
+        )?;
+        Ok(buffer)
+    }
+
+    /// Allocates a buffer with the size of the file region and reads the file into it with
+    /// `/b_allocRead`. If `num_of_frames` is less or equal to zero, the entire file is read.
+    pub fn alloc_read<F>(
+        server: &Server,
+        number: i32,
+        path: &str,
+        start_frame: i32,
+        num_of_frames: i32,
+        on_done: F,
+    ) -> ScClientResult<Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        let buffer = Buffer::new(server, number);
+        buffer.send_command(
+            "/b_allocRead",
+            vec![path.into(), start_frame.into(), num_of_frames.into()],
+            on_done,
+        )?;
+        Ok(buffer)
+    }
+
+    /// The same as [`alloc_read`](#method.alloc_read), but reads only the specified channels
+    /// of the file (`/b_allocReadChannel`).
+    pub fn alloc_read_channel<F>(
+        server: &Server,
+        number: i32,
+        path: &str,
+        start_frame: i32,
+        num_of_frames: i32,
+        channels: &[i32],
+        on_done: F,
+    ) -> ScClientResult<Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        let buffer = Buffer::new(server, number);
+        let mut send_args: Vec<OscType> =
+            vec![path.into(), start_frame.into(), num_of_frames.into()];
+        send_args.extend(channels.iter().map(|&channel| OscType::Int(channel)));
+        buffer.send_command("/b_allocReadChannel", send_args, on_done)?;
+        Ok(buffer)
+    }
+
+    /// Reads the file into the already allocated buffer with `/b_read`.
+    pub fn read<F>(
+        &self,
+        path: &str,
+        options: &BufferReadOptions,
+        on_done: F,
+    ) -> ScClientResult<&Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        self.send_command("/b_read", options.to_args(path), on_done)?;
+        Ok(self)
+    }
+
+    /// The same as [`read`](#method.read), but reads only the specified channels of the file
+    /// (`/b_readChannel`).
+    pub fn read_channel<F>(
+        &self,
+        path: &str,
+        options: &BufferReadOptions,
+        channels: &[i32],
+        on_done: F,
+    ) -> ScClientResult<&Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        let mut send_args = options.to_args(path);
+        send_args.extend(channels.iter().map(|&channel| OscType::Int(channel)));
+        self.send_command("/b_readChannel", send_args, on_done)?;
+        Ok(self)
+    }
+
+    /// Writes the buffer into a sound file with `/b_write`.
+    pub fn write<F>(
+        &self,
+        path: &str,
+        options: &BufferWriteOptions,
+        on_done: F,
+    ) -> ScClientResult<&Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        self.send_command("/b_write", options.to_args(path), on_done)?;
+        Ok(self)
+    }
+
+    /// Frees the buffer's memory on the server (`/b_free`).
+    pub fn free<F>(&self, on_done: F) -> ScClientResult<&Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        self.send_command("/b_free", vec![], on_done)?;
+        Ok(self)
+    }
+
+    /// Sets all the samples to zero (`/b_zero`).
+    pub fn zero<F>(&self, on_done: F) -> ScClientResult<&Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        self.send_command("/b_zero", vec![], on_done)?;
+        Ok(self)
+    }
+
+    /// Closes the sound file, which was left open by `read` or `write` (`/b_close`).
+    pub fn close<F>(&self, on_done: F) -> ScClientResult<&Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        self.send_command("/b_close", vec![], on_done)?;
+        Ok(self)
+    }
+
+    /// Queries info of the buffers with `/b_query` and performs callback with
+    /// [`BufferInfo`](struct.BufferInfo.html) for each buffer in the same order.
+    pub fn query<F>(server: &Server, numbers: &[i32], on_reply: F) -> ScClientResult<()>
+    where
+        F: Fn(Vec<BufferInfo>) + Send + Sync + 'static,
+    {
+        let messages = Buffer::query_messages(numbers);
+        request_values(
+            &server.osc_server,
+            "/b_query",
+            messages,
+            on_reply,
+            |on_reply| BufferInfoResponder::new(numbers.to_vec(), on_reply),
+        )
+    }
+
+    /// Returns the arguments of the `/b_query` messages for the buffers.
+    pub(crate) fn query_messages(numbers: &[i32]) -> Vec<Vec<OscType>> {
+        index_messages(&[], numbers, OscServer::max_num_of_args("/b_info") / 4)
+    }
+
+    /// Queries info of the buffer and performs callback with
+    /// [`BufferInfo`](struct.BufferInfo.html) as the parameter.
+    pub fn get_info<F>(&self, on_reply: F) -> ScClientResult<&Self>
+    where
+        F: Fn(BufferInfo) + Send + Sync + 'static,
+    {
+        Buffer::query(&self.server, &[self.number], move |mut infos| {
+            on_reply(infos.remove(0))
+        })?;
+        Ok(self)
+    }
+
+    /// Fills the buffer with a wave or transforms its data with `/b_gen`.
+    pub fn generate<F>(&self, command: &BufferGen, on_done: F) -> ScClientResult<&Self>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        self.send_command("/b_gen", command.to_args(), on_done)?;
+        Ok(self)
+    }
+
+    /// Sets samples at the indices with `/b_set`. Each tuple is `(sample_index, value)`.
+    pub fn set(&self, samples: &[(i32, f32)]) -> ScClientResult<&Self> {
+        for chunk in samples.chunks(max_num_of_pairs("/b_set")) {
+            let args = chunk
+                .iter()
+                .flat_map(|&(index, value)| vec![index.into(), value.into()])
+                .collect();
+            self.send_message("/b_set", args)?;
+        }
+        Ok(self)
+    }
+
+    /// Sets sequential samples, starting at `start_index`, with `/b_setn`.
+    pub fn set_range(&self, start_index: i32, samples: &[f32]) -> ScClientResult<&Self> {
+        let chunk_size = max_num_of_range_values("/b_setn");
+        for (chunk_index, chunk) in samples.chunks(chunk_size).enumerate() {
+            let chunk_start = start_index + (chunk_index * chunk_size) as i32;
+            let mut args = vec![chunk_start.into(), (chunk.len() as i32).into()];
+            args.extend(chunk.iter().map(|&value| OscType::Float(value)));
+            self.send_message("/b_setn", args)?;
+        }
+        Ok(self)
+    }
+
+    /// Sets `num_of_samples` sequential samples, starting at `start_index`, to `value`
+    /// (`/b_fill`).
+    pub fn fill(&self, start_index: i32, num_of_samples: i32, value: f32) -> ScClientResult<&Self> {
+        self.send_message(
+            "/b_fill",
+            vec![start_index.into(), num_of_samples.into(), value.into()],
+        )?;
+        Ok(self)
+    }
+
+    /// Gets samples at the indices with `/b_get` and performs callback with the values in the
+    /// same order.
+    pub fn get<F>(&self, indices: &[i32], on_reply: F) -> ScClientResult<&Self>
+    where
+        F: Fn(Vec<f32>) + Send + Sync + 'static,
+    {
+        let prefix = [self.number.into()];
+        let messages = index_messages(&prefix, indices, max_num_of_pairs("/b_set"));
+        request_values(
+            &self.server.osc_server,
+            "/b_get",
+            messages,
+            on_reply,
+            |on_reply| {
+                BufferValuesResponder::new("/b_set", self.number, indices.to_vec(), on_reply)
+            },
+        )?;
+        Ok(self)
+    }
+
+    /// Gets `num_of_samples` sequential samples, starting at `start_index`, with `/b_getn` and
+    /// performs callback with all the values. Fails with
+    /// [`ScClientError::InvalidRange`](enum.ScClientError.html), if the last index overflows.
+    pub fn get_range<F>(
+        &self,
+        start_index: i32,
+        num_of_samples: i32,
+        on_reply: F,
+    ) -> ScClientResult<&Self>
+    where
+        F: Fn(Vec<f32>) + Send + Sync + 'static,
+    {
+        let range = checked_range(start_index, num_of_samples)?;
+        let prefix = [self.number.into()];
+        let chunk_size = max_num_of_range_values("/b_setn");
+        let messages = range_messages(&prefix, range.clone(), chunk_size);
+        request_values(
+            &self.server.osc_server,
+            "/b_getn",
+            messages,
+            on_reply,
+            |on_reply| {
+                BufferValuesResponder::new("/b_setn", self.number, range.collect(), on_reply)
+            },
+        )?;
+        Ok(self)
+    }
+
+    fn send_message(&self, command: &str, mut args: Vec<OscType>) -> ScClientResult<()> {
+        let mut send_args = vec![self.number.into()];
+        send_args.append(&mut args);
+        self.server
+            .osc_server
+            .send_message(command, Some(send_args))?;
+        Ok(())
+    }
+
+    fn send_command<F>(&self, command: &str, args: Vec<OscType>, on_done: F) -> ScClientResult<()>
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        let responder = BufferDoneResponder::new(command, self.number, on_done);
+        self.server.osc_server.add_responder(responder)?;
+        self.send_message(command, args)
+    }
+
+    pub fn get_number(&self) -> i32 {
+        self.number
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BufferInfo {
+    /// buffer number.
+    pub number: i32,
+    /// number of frames.
+    pub num_of_frames: i32,
+    /// number of channels.
+    pub num_of_channels: i32,
+    /// sample rate.
+    pub sample_rate: f32,
+}
+
+/// Maximum number of `index, value` pairs in a message, which is prefixed with the buffer
+/// number.
+fn max_num_of_pairs(address: &str) -> usize {
+    (OscServer::max_num_of_args(address) - 1) / 2
+}
+
+/// Maximum number of values in a message, which is prefixed with the buffer number, start
+/// index and number of values.
+fn max_num_of_range_values(address: &str) -> usize {
+    OscServer::max_num_of_args(address) - 3
+}
+
+#[derive(Clone, Debug)]
+pub struct BufferReadOptions {
+    /// the frame of the file to start reading at.
+    pub file_start_frame: i32,
+    /// number of frames to read, -1 to read the whole file.
+    pub num_of_frames: i32,
+    /// the frame of the buffer to start writing at.
+    pub buffer_start_frame: i32,
+    /// leave the file open. Used with `DiskIn` UGen.
+    pub leave_open: bool,
+}
+
+impl BufferReadOptions {
+    fn to_args(&self, path: &str) -> Vec<OscType> {
+        vec![
+            path.into(),
+            self.file_start_frame.into(),
+            self.num_of_frames.into(),
+            self.buffer_start_frame.into(),
+            (self.leave_open as i32).into(),
+        ]
+    }
+}
+
+impl Default for BufferReadOptions {
+    fn default() -> Self {
+        BufferReadOptions {
+            file_start_frame: 0,
+            num_of_frames: -1,
+            buffer_start_frame: 0,
+            leave_open: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BufferWriteOptions {
+    pub header_format: HeaderFormat,
+    pub sample_format: SampleFormat,
+    /// number of frames to write, -1 to write the whole buffer.
+    pub num_of_frames: i32,
+    /// the frame of the buffer to start writing from.
+    pub start_frame: i32,
+    /// leave the file open. Used with `DiskOut` UGen.
+    pub leave_open: bool,
+}
+
+impl BufferWriteOptions {
+    fn to_args(&self, path: &str) -> Vec<OscType> {
+        vec![
+            path.into(),
+            self.header_format.as_str().into(),
+            self.sample_format.as_str().into(),
+            self.num_of_frames.into(),
+            self.start_frame.into(),
+            (self.leave_open as i32).into(),
+        ]
+    }
+}
+
+impl Default for BufferWriteOptions {
+    fn default() -> Self {
+        BufferWriteOptions {
+            header_format: HeaderFormat::Wav,
+            sample_format: SampleFormat::Float,
+            num_of_frames: -1,
+            start_frame: 0,
+            leave_open: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum HeaderFormat {
+    Aiff,
+    Next,
+    Wav,
+    Ircam,
+    Raw,
+}
+
+impl HeaderFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HeaderFormat::Aiff => "aiff",
+            HeaderFormat::Next => "next",
+            HeaderFormat::Wav => "wav",
+            HeaderFormat::Ircam => "ircam",
+            HeaderFormat::Raw => "raw",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum SampleFormat {
+    Int8,
+    Int16,
+    Int24,
+    Int32,
+    Float,
+    Double,
+    Mulaw,
+    Alaw,
+}
+
+impl SampleFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SampleFormat::Int8 => "int8",
+            SampleFormat::Int16 => "int16",
+            SampleFormat::Int24 => "int24",
+            SampleFormat::Int32 => "int32",
+            SampleFormat::Float => "float",
+            SampleFormat::Double => "double",
+            SampleFormat::Mulaw => "mulaw",
+            SampleFormat::Alaw => "alaw",
+        }
+    }
+}
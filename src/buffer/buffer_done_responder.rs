@@ -0,0 +1,41 @@
+use crate::{types::OscMessage, types::OscType, AfterCallAction, OscResponder, ScClientResult};
+use std::sync::Mutex;
+
+/// Waits for `/done` of a buffer command for the specific buffer number.
+pub struct BufferDoneResponder<F: Fn(i32) + Send + Sync + 'static> {
+    on_done_callback: F,
+    command: String,
+    buffer_number: i32,
+    after_call_action: Mutex<AfterCallAction>,
+}
+
+impl<F: Fn(i32) + Send + Sync + 'static> BufferDoneResponder<F> {
+    pub fn new(command: &str, buffer_number: i32, on_done_callback: F) -> Self {
+        BufferDoneResponder {
+            on_done_callback,
+            command: command.to_string(),
+            buffer_number,
+            after_call_action: Mutex::new(AfterCallAction::Reschedule),
+        }
+    }
+}
+
+impl<F: Fn(i32) + Send + Sync + 'static> OscResponder for BufferDoneResponder<F> {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        if let Some(ref args) = message.args {
+            if args.get(1) == Some(&OscType::Int(self.buffer_number)) {
+                (self.on_done_callback)(self.buffer_number);
+                *self.after_call_action.lock().unwrap() = AfterCallAction::None;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_address(&self) -> String {
+        self.command.clone()
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        (*self.after_call_action.lock().unwrap()).clone()
+    }
+}
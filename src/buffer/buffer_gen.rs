@@ -0,0 +1,110 @@
+use crate::types::OscType;
+
+/// Flags of the wave fill commands (`sine1`, `sine2`, `sine3` and `cheby`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BufferGenFlags {
+    /// normalize peak amplitude of the wave to 1.0.
+    pub normalize: bool,
+    /// write the wave in the wavetable format, so it can be read by interpolating oscillators.
+    pub wavetable: bool,
+    /// clear the buffer before writing the wave, otherwise the wave is added to the existing
+    /// data.
+    pub clear: bool,
+}
+
+impl BufferGenFlags {
+    pub fn to_int(&self) -> i32 {
+        (self.normalize as i32) | (self.wavetable as i32) << 1 | (self.clear as i32) << 2
+    }
+}
+
+/// Commands for [`Buffer::generate`](struct.Buffer.html#method.generate) (`/b_gen`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum BufferGen {
+    /// a series of sine partials. The first partial is the fundamental, the second is the
+    /// first harmonic and so on.
+    Sine1 {
+        flags: BufferGenFlags,
+        amplitudes: Vec<f32>,
+    },
+    /// sine partials of any frequencies. Each pair is `(frequency, amplitude)`, where the
+    /// frequency is in cycles per buffer.
+    Sine2 {
+        flags: BufferGenFlags,
+        partials: Vec<(f32, f32)>,
+    },
+    /// sine partials with phases. Each triplet is `(frequency, amplitude, phase)`, where the
+    /// frequency is in cycles per buffer and phase is in radians.
+    Sine3 {
+        flags: BufferGenFlags,
+        partials: Vec<(f32, f32, f32)>,
+    },
+    /// a series of Chebyshev polynomials, which can be used as a waveshaping transfer function.
+    Cheby {
+        flags: BufferGenFlags,
+        amplitudes: Vec<f32>,
+    },
+    /// copy samples from another buffer. If `num_of_frames` is negative, the maximum number of
+    /// frames is copied.
+    Copy {
+        dest_start_frame: i32,
+        source_buffer_number: i32,
+        source_start_frame: i32,
+        num_of_frames: i32,
+    },
+    /// normalize the buffer to the new peak amplitude.
+    Normalize(f32),
+    /// normalize the buffer, which is in the wavetable format, to the new peak amplitude.
+    WavetableNormalize(f32),
+}
+
+impl BufferGen {
+    pub fn to_args(&self) -> Vec<OscType> {
+        match self {
+            BufferGen::Sine1 { flags, amplitudes } => {
+                BufferGen::wave_fill_args("sine1", flags, amplitudes.clone())
+            }
+            BufferGen::Sine2 { flags, partials } => BufferGen::wave_fill_args(
+                "sine2",
+                flags,
+                partials
+                    .iter()
+                    .flat_map(|&(frequency, amplitude)| vec![frequency, amplitude])
+                    .collect(),
+            ),
+            BufferGen::Sine3 { flags, partials } => BufferGen::wave_fill_args(
+                "sine3",
+                flags,
+                partials
+                    .iter()
+                    .flat_map(|&(frequency, amplitude, phase)| vec![frequency, amplitude, phase])
+                    .collect(),
+            ),
+            BufferGen::Cheby { flags, amplitudes } => {
+                BufferGen::wave_fill_args("cheby", flags, amplitudes.clone())
+            }
+            BufferGen::Copy {
+                dest_start_frame,
+                source_buffer_number,
+                source_start_frame,
+                num_of_frames,
+            } => vec![
+                "copy".into(),
+                (*dest_start_frame).into(),
+                (*source_buffer_number).into(),
+                (*source_start_frame).into(),
+                (*num_of_frames).into(),
+            ],
+            BufferGen::Normalize(new_max) => vec!["normalize".into(), (*new_max).into()],
+            BufferGen::WavetableNormalize(new_max) => {
+                vec!["wnormalize".into(), (*new_max).into()]
+            }
+        }
+    }
+
+    fn wave_fill_args(command: &str, flags: &BufferGenFlags, values: Vec<f32>) -> Vec<OscType> {
+        let mut args = vec![command.into(), flags.to_int().into()];
+        args.extend(values.into_iter().map(OscType::Float));
+        args
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    types::OscMessage, types::OscType, AfterCallAction, BufferInfo, OscResponder, ScClientResult,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Collects `/b_info` replies, which can come in several messages, and calls back with the
+/// infos in the order of the requested buffer numbers.
+pub struct BufferInfoResponder<F: Fn(Vec<BufferInfo>) + Send + Sync + 'static> {
+    on_reply_callback: F,
+    buffer_numbers: Vec<i32>,
+    received_infos: Mutex<HashMap<i32, BufferInfo>>,
+    after_call_action: Mutex<AfterCallAction>,
+}
+
+impl<F: Fn(Vec<BufferInfo>) + Send + Sync + 'static> BufferInfoResponder<F> {
+    pub fn new(buffer_numbers: Vec<i32>, on_reply_callback: F) -> Self {
+        BufferInfoResponder {
+            on_reply_callback,
+            buffer_numbers,
+            received_infos: Mutex::new(HashMap::new()),
+            after_call_action: Mutex::new(AfterCallAction::Reschedule),
+        }
+    }
+}
+
+impl<F: Fn(Vec<BufferInfo>) + Send + Sync + 'static> OscResponder for BufferInfoResponder<F> {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        if let Some(ref args) = message.args {
+            let mut received_infos = self.received_infos.lock().unwrap();
+            for info in args.chunks(4).filter_map(parse_info) {
+                if self.buffer_numbers.contains(&info.number) {
+                    received_infos.insert(info.number, info);
+                }
+            }
+
+            let infos: Option<Vec<BufferInfo>> = self
+                .buffer_numbers
+                .iter()
+                .map(|number| received_infos.get(number).cloned())
+                .collect();
+            if let Some(infos) = infos {
+                (self.on_reply_callback)(infos);
+                *self.after_call_action.lock().unwrap() = AfterCallAction::None;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_address(&self) -> String {
+        String::from("/b_info")
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        (*self.after_call_action.lock().unwrap()).clone()
+    }
+}
+
+fn parse_info(args: &[OscType]) -> Option<BufferInfo> {
+    let int_at = |index: usize| match args.get(index) {
+        Some(OscType::Int(n)) => Some(*n),
+        _ => None,
+    };
+    let sample_rate = match args.get(3) {
+        Some(OscType::Float(rate)) => *rate,
+        _ => return None,
+    };
+    Some(BufferInfo {
+        number: int_at(0)?,
+        num_of_frames: int_at(1)?,
+        num_of_channels: int_at(2)?,
+        sample_rate,
+    })
+}
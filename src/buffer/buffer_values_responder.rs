@@ -0,0 +1,51 @@
+use crate::value_collector::ValueCollector;
+use crate::{types::OscMessage, types::OscType, AfterCallAction, OscResponder, ScClientResult};
+use std::sync::Mutex;
+
+/// Collects sample values from `/b_set` or `/b_setn` replies, which can come in several
+/// messages, and calls back with all of them in the order of the requested indices.
+pub struct BufferValuesResponder<F: Fn(Vec<f32>) + Send + Sync + 'static> {
+    on_reply_callback: F,
+    address: String,
+    buffer_number: i32,
+    collector: Mutex<ValueCollector>,
+    after_call_action: Mutex<AfterCallAction>,
+}
+
+impl<F: Fn(Vec<f32>) + Send + Sync + 'static> BufferValuesResponder<F> {
+    pub fn new(address: &str, buffer_number: i32, indices: Vec<i32>, on_reply_callback: F) -> Self {
+        BufferValuesResponder {
+            on_reply_callback,
+            address: address.to_string(),
+            buffer_number,
+            collector: Mutex::new(ValueCollector::new(indices, address == "/b_setn")),
+            after_call_action: Mutex::new(AfterCallAction::Reschedule),
+        }
+    }
+}
+
+impl<F: Fn(Vec<f32>) + Send + Sync + 'static> OscResponder for BufferValuesResponder<F> {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        if let Some(ref args) = message.args {
+            if args.first() != Some(&OscType::Int(self.buffer_number)) {
+                return Ok(());
+            }
+
+            let mut collector = self.collector.lock().unwrap();
+            collector.collect(&args[1..]);
+            if let Some(values) = collector.values() {
+                (self.on_reply_callback)(values);
+                *self.after_call_action.lock().unwrap() = AfterCallAction::None;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_address(&self) -> String {
+        self.address.clone()
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        (*self.after_call_action.lock().unwrap()).clone()
+    }
+}
@@ -0,0 +1,98 @@
+use super::*;
+use crate::{types::OscMessage, AfterCallAction, OscResponder};
+use rosc::decoder::MTU;
+use rosc::{encoder, OscPacket};
+use std::sync::{Arc, Mutex};
+
+fn encoded_size(address: &str, num_of_args: usize) -> usize {
+    let message = OscMessage {
+        addr: address.to_string(),
+        args: Some(vec![OscType::Float(0.0); num_of_args]),
+    };
+    encoder::encode(&OscPacket::Message(message)).unwrap().len()
+}
+
+#[test]
+fn max_num_of_args_fits_into_mtu() {
+    for address in &["/b_set", "/b_setn", "/b_gen"] {
+        let num_of_args = OscServer::max_num_of_args(address);
+        assert!(encoded_size(address, num_of_args) <= MTU);
+        assert!(encoded_size(address, num_of_args + 1) > MTU);
+    }
+}
+
+#[test]
+fn collect_range_values_from_several_replies() {
+    let values = Arc::new(Mutex::new(Vec::new()));
+    let reply_values = values.clone();
+    let responder = BufferValuesResponder::new("/b_setn", 3, (10..15).collect(), move |v| {
+        *reply_values.lock().unwrap() = v
+    });
+    let reply = |args: Vec<OscType>| OscMessage {
+        addr: String::from("/b_setn"),
+        args: Some(args),
+    };
+
+    responder
+        .callback(&reply(vec![
+            3.into(),
+            13.into(),
+            2.into(),
+            0.4f32.into(),
+            0.5f32.into(),
+        ]))
+        .unwrap();
+    assert!(responder.get_after_call_action(&reply(vec![])) == AfterCallAction::Reschedule);
+    // another buffer
+    responder
+        .callback(&reply(vec![4.into(), 10.into(), 1.into(), 1.0f32.into()]))
+        .unwrap();
+    responder
+        .callback(&reply(vec![
+            3.into(),
+            10.into(),
+            3.into(),
+            0.1f32.into(),
+            0.2f32.into(),
+            0.3f32.into(),
+        ]))
+        .unwrap();
+
+    assert!(responder.get_after_call_action(&reply(vec![])) == AfterCallAction::None);
+    assert_eq!(vec![0.1, 0.2, 0.3, 0.4, 0.5], *values.lock().unwrap());
+}
+
+#[test]
+fn buffer_gen_args() {
+    let flags = BufferGenFlags {
+        normalize: true,
+        wavetable: true,
+        clear: true,
+    };
+    assert_eq!(7, flags.to_int());
+    assert_eq!(0, BufferGenFlags::default().to_int());
+
+    let command = BufferGen::Sine2 {
+        flags: BufferGenFlags {
+            wavetable: true,
+            ..BufferGenFlags::default()
+        },
+        partials: vec![(1.0, 0.5), (3.0, 0.25)],
+    };
+    assert_eq!(
+        vec![
+            OscType::from("sine2"),
+            2.into(),
+            1.0f32.into(),
+            0.5f32.into(),
+            3.0f32.into(),
+            0.25f32.into(),
+        ],
+        command.to_args()
+    );
+
+    assert_eq!(
+        vec![OscType::from("wnormalize"), 1.0f32.into()],
+        BufferGen::WavetableNormalize(1.0).to_args()
+    );
+}
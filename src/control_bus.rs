@@ -0,0 +1,129 @@
+mod control_bus_values_responder;
+#[cfg(test)]
+mod tests;
+use self::control_bus_values_responder::ControlBusValuesResponder;
+use crate::value_request::{checked_range, index_messages, range_messages, request_values};
+use crate::{types::OscType, OscServer, ScClientResult, Server};
+
+/// A range of sequential control buses.
+///
+/// Channels are specified relatively to the bus index, so the channel `0` is the bus at
+/// `index`. Values are sent and requested in chunks, so that every message fits into a UDP
+/// packet.
+#[derive(Clone)]
+pub struct ControlBus {
+    index: i32,
+    num_of_channels: i32,
+    server: Server,
+}
+
+impl ControlBus {
+    pub fn new(server: &Server, index: i32, num_of_channels: i32) -> Self {
+        ControlBus {
+            index,
+            num_of_channels,
+            server: server.clone(),
+        }
+    }
+
+    /// Allocates `num_of_channels` sequential buses with the server's allocator.
+    pub fn alloc(server: &Server, num_of_channels: i32) -> ScClientResult<Self> {
+        let index = server.allocate_control_bus(num_of_channels)?;
+        Ok(ControlBus::new(server, index, num_of_channels))
+    }
+
+    /// Returns the buses to the server's allocator. The bus should be created with
+    /// [`alloc`](#method.alloc).
+    pub fn free(&self) -> ScClientResult<()> {
+        self.server.free_control_bus(self.index)?;
+        Ok(())
+    }
+
+    /// Sets values of the channels with `/c_set`. Each tuple is `(channel, value)`.
+    pub fn set(&self, values: &[(i32, f32)]) -> ScClientResult<&Self> {
+        for chunk in values.chunks(OscServer::max_num_of_args("/c_set") / 2) {
+            let args = chunk
+                .iter()
+                .flat_map(|&(channel, value)| vec![(self.index + channel).into(), value.into()])
+                .collect();
+            self.send_message("/c_set", args)?;
+        }
+        Ok(self)
+    }
+
+    /// Sets values of sequential channels, starting at `start_channel`, with `/c_setn`.
+    pub fn set_range(&self, start_channel: i32, values: &[f32]) -> ScClientResult<&Self> {
+        let chunk_size = OscServer::max_num_of_args("/c_setn") - 2;
+        for (chunk_index, chunk) in values.chunks(chunk_size).enumerate() {
+            let chunk_start = self.index + start_channel + (chunk_index * chunk_size) as i32;
+            let mut args = vec![chunk_start.into(), (chunk.len() as i32).into()];
+            args.extend(chunk.iter().map(|&value| OscType::Float(value)));
+            self.send_message("/c_setn", args)?;
+        }
+        Ok(self)
+    }
+
+    /// Sets all the channels of the bus to `value` (`/c_fill`).
+    pub fn fill(&self, value: f32) -> ScClientResult<&Self> {
+        self.send_message(
+            "/c_fill",
+            vec![self.index.into(), self.num_of_channels.into(), value.into()],
+        )?;
+        Ok(self)
+    }
+
+    /// Gets values of the channels with `/c_get` and performs callback with the values in the
+    /// same order.
+    pub fn get<F>(&self, channels: &[i32], on_reply: F) -> ScClientResult<&Self>
+    where
+        F: Fn(Vec<f32>) + Send + Sync + 'static,
+    {
+        let bus_indices: Vec<i32> = channels
+            .iter()
+            .map(|channel| self.index + channel)
+            .collect();
+        let chunk_size = OscServer::max_num_of_args("/c_set") / 2;
+        let messages = index_messages(&[], &bus_indices, chunk_size);
+        request_values(
+            &self.server.osc_server,
+            "/c_get",
+            messages,
+            on_reply,
+            |on_reply| ControlBusValuesResponder::new("/c_set", bus_indices, on_reply),
+        )?;
+        Ok(self)
+    }
+
+    /// Gets values of all the channels of the bus with `/c_getn` and performs callback with
+    /// them. Fails with [`ScClientError::InvalidRange`](enum.ScClientError.html), if the last
+    /// bus index overflows.
+    pub fn get_all<F>(&self, on_reply: F) -> ScClientResult<&Self>
+    where
+        F: Fn(Vec<f32>) + Send + Sync + 'static,
+    {
+        let range = checked_range(self.index, self.num_of_channels)?;
+        let chunk_size = OscServer::max_num_of_args("/c_setn") - 2;
+        let messages = range_messages(&[], range.clone(), chunk_size);
+        request_values(
+            &self.server.osc_server,
+            "/c_getn",
+            messages,
+            on_reply,
+            |on_reply| ControlBusValuesResponder::new("/c_setn", range.collect(), on_reply),
+        )?;
+        Ok(self)
+    }
+
+    fn send_message(&self, command: &str, args: Vec<OscType>) -> ScClientResult<()> {
+        self.server.osc_server.send_message(command, Some(args))?;
+        Ok(())
+    }
+
+    pub fn get_index(&self) -> i32 {
+        self.index
+    }
+
+    pub fn get_num_of_channels(&self) -> i32 {
+        self.num_of_channels
+    }
+}
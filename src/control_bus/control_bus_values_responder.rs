@@ -0,0 +1,45 @@
+use crate::value_collector::ValueCollector;
+use crate::{types::OscMessage, AfterCallAction, OscResponder, ScClientResult};
+use std::sync::Mutex;
+
+/// Collects bus values from `/c_set` or `/c_setn` replies, which can come in several
+/// messages, and calls back with all of them in the order of the requested bus indices.
+pub struct ControlBusValuesResponder<F: Fn(Vec<f32>) + Send + Sync + 'static> {
+    on_reply_callback: F,
+    address: String,
+    collector: Mutex<ValueCollector>,
+    after_call_action: Mutex<AfterCallAction>,
+}
+
+impl<F: Fn(Vec<f32>) + Send + Sync + 'static> ControlBusValuesResponder<F> {
+    pub fn new(address: &str, bus_indices: Vec<i32>, on_reply_callback: F) -> Self {
+        ControlBusValuesResponder {
+            on_reply_callback,
+            address: address.to_string(),
+            collector: Mutex::new(ValueCollector::new(bus_indices, address == "/c_setn")),
+            after_call_action: Mutex::new(AfterCallAction::Reschedule),
+        }
+    }
+}
+
+impl<F: Fn(Vec<f32>) + Send + Sync + 'static> OscResponder for ControlBusValuesResponder<F> {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        if let Some(ref args) = message.args {
+            let mut collector = self.collector.lock().unwrap();
+            collector.collect(args);
+            if let Some(values) = collector.values() {
+                (self.on_reply_callback)(values);
+                *self.after_call_action.lock().unwrap() = AfterCallAction::None;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_address(&self) -> String {
+        self.address.clone()
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        (*self.after_call_action.lock().unwrap()).clone()
+    }
+}
@@ -0,0 +1,32 @@
+use super::*;
+use crate::{types::OscMessage, AfterCallAction, OscResponder};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn values_responder_calls_back_when_all_values_are_received() {
+    let values = Arc::new(Mutex::new(Vec::new()));
+    let reply_values = values.clone();
+    let responder = ControlBusValuesResponder::new("/c_setn", vec![4, 5, 6], move |v| {
+        *reply_values.lock().unwrap() = v
+    });
+    let reply = |args: Vec<OscType>| OscMessage {
+        addr: String::from("/c_setn"),
+        args: Some(args),
+    };
+
+    responder
+        .callback(&reply(vec![6.into(), 1.into(), 0.6f32.into()]))
+        .unwrap();
+    assert!(responder.get_after_call_action(&reply(vec![])) == AfterCallAction::Reschedule);
+    responder
+        .callback(&reply(vec![
+            4.into(),
+            2.into(),
+            0.4f32.into(),
+            0.5f32.into(),
+        ]))
+        .unwrap();
+
+    assert!(responder.get_after_call_action(&reply(vec![])) == AfterCallAction::None);
+    assert_eq!(vec![0.4, 0.5, 0.6], *values.lock().unwrap());
+}
@@ -0,0 +1,194 @@
+use crate::types::{OscMessage, OscType};
+use rosc::OscError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::net::AddrParseError;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+/// Errors of all the operations of the crate.
+#[derive(Debug)]
+pub enum ScClientError {
+    /// a received packet isn't a valid OSC packet.
+    Decode(OscError),
+    /// a message can't be encoded into an OSC packet.
+    Encode(OscError),
+    /// an I/O error of the connection or the server process.
+    Io(io::Error),
+    /// an address isn't in the `ip:port` format.
+    InvalidAddress(AddrParseError),
+    /// the address is reserved for the internal responders.
+    ReservedAddress(String),
+    /// a responder can't be added or called.
+    Responder(String),
+    /// a bundle is already started in the current thread.
+    BundleAlreadyStarted,
+    /// there's no started bundle in the current thread.
+    BundleNotStarted,
+    /// the server replied with `/fail` to a command.
+    ServerFailure(ServerFailure),
+    /// the server hasn't replied to `/sync` in the specified time.
+    SyncTimeout(Duration),
+    /// the server hasn't replied to a request in the specified time.
+    ReplyTimeout(Duration),
+    /// the server hasn't replied to the request or the responder is gone without calling back.
+    NoReply,
+    /// the server process can't be started.
+    ProcessStart { path: String, source: io::Error },
+    /// the server process is already running.
+    AlreadyRunning,
+    /// the server process hasn't got ready in the specified time. Contains its output.
+    BootTimeout {
+        timeout: Duration,
+        output: Vec<String>,
+    },
+    /// the server process has exited, before it got ready. Contains its output.
+    ProcessExited {
+        status: ExitStatus,
+        output: Vec<String>,
+    },
+    /// the server process has printed the failure `line`, so it won't get ready. Contains
+    /// its output.
+    BootFailed { line: String, output: Vec<String> },
+    /// a block of the size can't be allocated.
+    InvalidBlockSize(i32),
+    /// there's no free block of the size.
+    NotEnoughSpace(i32),
+    /// there's no allocated block at the address.
+    NotAllocated(i32),
+    /// the range of `count` indices, starting at `start`, doesn't fit into `i32`.
+    InvalidRange { start: i32, count: i32 },
+}
+
+impl fmt::Display for ScClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScClientError::Decode(e) => write!(f, "Error decode packet: {:?}", e),
+            ScClientError::Encode(e) => write!(f, "Error encode message: {:?}", e),
+            ScClientError::Io(e) => write!(f, "I/O error: {}", e),
+            ScClientError::InvalidAddress(e) => write!(f, "Invalid address: {}", e),
+            ScClientError::ReservedAddress(address) => {
+                write!(f, "Error add responder: {} is reserved", address)
+            }
+            ScClientError::Responder(e) => write!(f, "Error call responder: {}", e),
+            ScClientError::BundleAlreadyStarted => {
+                write!(f, "Error start bundle: the bundle is already started")
+            }
+            ScClientError::BundleNotStarted => {
+                write!(f, "Error finish bundle: the bundle is not started")
+            }
+            ScClientError::ServerFailure(failure) => write!(f, "{}", failure),
+            ScClientError::SyncTimeout(timeout) => {
+                write!(f, "Error sync: the server hasn't replied in {:?}", timeout)
+            }
+            ScClientError::ReplyTimeout(timeout) => write!(
+                f,
+                "Error wait for reply: the server hasn't replied in {:?}",
+                timeout
+            ),
+            ScClientError::NoReply => write!(
+                f,
+                "Error wait for reply: the responder was dropped without reply"
+            ),
+            ScClientError::ProcessStart { path, source } => {
+                write!(f, "Couldn't start {}: {}", path, source)
+            }
+            ScClientError::AlreadyRunning => write!(f, "SuperCollider server is already running"),
+            ScClientError::BootTimeout { timeout, .. } => {
+                write!(f, "SuperCollider server hasn't got ready in {:?}", timeout)
+            }
+            ScClientError::ProcessExited { status, .. } => {
+                write!(f, "SuperCollider server has exited on boot with {}", status)
+            }
+            ScClientError::BootFailed { line, .. } => {
+                write!(f, "SuperCollider server has failed to boot: {}", line)
+            }
+            ScClientError::InvalidBlockSize(size) => write!(f, "Invalid block size: {}", size),
+            ScClientError::NotEnoughSpace(size) => {
+                write!(f, "Not enough space to allocate a block of size {}", size)
+            }
+            ScClientError::NotAllocated(address) => {
+                write!(f, "Block at {} is not allocated", address)
+            }
+            ScClientError::InvalidRange { start, count } => {
+                write!(
+                    f,
+                    "Invalid range of {} indices, starting at {}",
+                    count, start
+                )
+            }
+        }
+    }
+}
+
+impl Error for ScClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScClientError::Io(e) | ScClientError::ProcessStart { source: e, .. } => Some(e),
+            ScClientError::InvalidAddress(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScClientError {
+    fn from(e: io::Error) -> Self {
+        ScClientError::Io(e)
+    }
+}
+
+impl From<AddrParseError> for ScClientError {
+    fn from(e: AddrParseError) -> Self {
+        ScClientError::InvalidAddress(e)
+    }
+}
+
+/// Decoded `/fail` reply of the server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerFailure {
+    /// the command, which has failed.
+    pub command: String,
+    /// the error message.
+    pub message: String,
+    /// the rest of the arguments, for example, the buffer number of the buffer commands.
+    pub extra_args: Vec<OscType>,
+}
+
+impl ServerFailure {
+    /// Decodes `/fail command message ...`. Returns `None` if it's not a `/fail` message or the
+    /// command is missing.
+    pub fn from_message(message: &OscMessage) -> Option<Self> {
+        if message.addr != "/fail" {
+            return None;
+        }
+        let args = message.args.as_deref().unwrap_or_default();
+        let command = match args.first() {
+            Some(OscType::String(command)) => command.clone(),
+            _ => return None,
+        };
+        let message = match args.get(1) {
+            Some(OscType::String(message)) => message.clone(),
+            _ => String::new(),
+        };
+        Some(ServerFailure {
+            command,
+            message,
+            extra_args: args.iter().skip(2).cloned().collect(),
+        })
+    }
+}
+
+impl fmt::Display for ServerFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Server failed to perform {}: {}",
+            self.command, self.message
+        )?;
+        if !self.extra_args.is_empty() {
+            write!(f, " {:?}", self.extra_args)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{types::OscType, AddAction, Node, ScClientResult, Server};
+
+#[derive(Clone)]
+pub struct Group {
+    id: i32,
+    target_id: i32,
+    server: Server,
+}
+
+impl Group {
+    /// Creates a new group on the server with `/g_new`.
+    pub fn new(server: &Server, add_action: &AddAction, target_id: i32) -> ScClientResult<Self> {
+        Group::new_with_command(server, "/g_new", add_action, target_id)
+    }
+
+    /// Creates a new parallel group with `/p_new`. Only supported by supernova, scsynth treats
+    /// it as a plain group.
+    pub fn new_parallel(
+        server: &Server,
+        add_action: &AddAction,
+        target_id: i32,
+    ) -> ScClientResult<Self> {
+        Group::new_with_command(server, "/p_new", add_action, target_id)
+    }
+
+    fn new_with_command(
+        server: &Server,
+        command: &str,
+        add_action: &AddAction,
+        target_id: i32,
+    ) -> ScClientResult<Self> {
+        let id = server.next_node_id();
+        let group = Group {
+            id,
+            target_id,
+            server: server.clone(),
+        };
+        group.init_on_server(command, add_action)?;
+        Ok(group)
+    }
+
+    fn init_on_server(&self, command: &str, add_action: &AddAction) -> ScClientResult<()> {
+        let send_args: Vec<OscType> = vec![
+            self.id.into(),
+            (add_action.clone() as i32).into(),
+            self.target_id.into(),
+        ];
+        self.server
+            .osc_server
+            .send_message(command, Some(send_args))?;
+
+        Ok(())
+    }
+
+    /// Moves the node with `node_id` to the head of the group.
+    pub fn move_node_to_head(&self, node_id: i32) -> ScClientResult<&Self> {
+        self.server
+            .osc_server
+            .send_message("/g_head", Some(vec![self.id.into(), node_id.into()]))?;
+        Ok(self)
+    }
+
+    /// Moves the node with `node_id` to the tail of the group.
+    pub fn move_node_to_tail(&self, node_id: i32) -> ScClientResult<&Self> {
+        self.server
+            .osc_server
+            .send_message("/g_tail", Some(vec![self.id.into(), node_id.into()]))?;
+        Ok(self)
+    }
+
+    /// Frees all the immediate children of the group. The group itself stays on the server.
+    pub fn free_all(&self) -> ScClientResult<&Self> {
+        self.server
+            .osc_server
+            .send_message("/g_freeAll", Some(vec![self.id.into()]))?;
+        Ok(self)
+    }
+
+    /// Frees all the synths in the group and in all its nested groups. The groups themselves
+    /// stay on the server.
+    pub fn deep_free(&self) -> ScClientResult<&Self> {
+        self.server
+            .osc_server
+            .send_message("/g_deepFree", Some(vec![self.id.into()]))?;
+        Ok(self)
+    }
+
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn get_target_id(&self) -> i32 {
+        self.target_id
+    }
+}
+
+impl Node for Group {
+    fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    fn get_server(&self) -> &Server {
+        &self.server
+    }
+}
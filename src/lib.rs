@@ -33,21 +33,41 @@
 //! | `real_time_memory_size`          | **Unsigned Integer** | `8192`                                        | The number of kilobytes of real time memory. This memory is used to allocate synths and any memory that unit generators themselves allocate.                                                                                                                                                                      |
 //! | `restricted_path`                | **String**           | `None`                                        | If specified, prevents file-accessing OSC commands from accessing files outside `restricted_path`.                                                                                                                                                                                                                |
 //! | `session_password`               | **String**           | `None`                                        | When using TCP, the session password must be the first command sent. UDP ports never require passwords, so if password protection is desired, use TCP.                                                                                                                                                            |
-//! | `tcp_port_number`                | **Unsigned Integer** | `0`                                           | A port number 0-65535. Only UDP supported. But the server will listen on TCP if you specify this option.                                                                                                                                                                                                          |
-//! | `udp_port_number`                | **Unsigned Integer** | `4242`                                        | A port number 0-65535. Only UDP supported.                                                                                                                                                                                                                                                                        |
+//! | `tcp_port_number`                | **Unsigned Integer** | `0`                                           | A port number 0-65535. The client uses TCP only if `udp_port_number` is 0.                                                                                                                                                                                                                                        |
+//! | `udp_port_number`                | **Unsigned Integer** | `4242`                                        | A port number 0-65535. Set it to 0 to use TCP.                                                                                                                                                                                                                                                                    |
 //! | `ugen_plugins_path`              | **Array of Strings** | `None`                                        | An array of paths. If specified, standard paths are NOT searched for plugins.                                                                                                                                                                                                                                     |
 //! | `verbosity`                      | **Integer**          | `0`                                           | Controls the verbosity of server messages. A value of 0 is normal behaviour. -1 suppresses informational messages. -2 suppresses informational and many error messages, as well as messages from Poll.                                                                                                            |
 //!
 //! > **Note**, `scsynth` has an [issue](https://github.com/supercollider/supercollider/issues/2488) whith setting the same sample rate, which was already set.
 //! > The workaround is to use `supernova` or not to set `preferred_sample_rate` for `scsynth` (or set it to `0`). You can set sample rate on your system's settings level.
+mod allocator;
+#[cfg(feature = "tokio")]
+mod async_server;
+mod buffer;
+mod control_bus;
+mod error;
+mod group;
+mod node;
+mod node_watcher;
 mod osc_server;
 mod server;
 mod synth;
 mod synth_definition;
 pub mod types;
+mod value_collector;
+mod value_request;
+pub use allocator::*;
+#[cfg(feature = "tokio")]
+pub use async_server::*;
+pub use buffer::*;
+pub use control_bus::*;
+pub use error::{ScClientError, ServerFailure};
+pub use group::*;
+pub use node::*;
+pub use node_watcher::*;
 pub use osc_server::*;
 pub use server::*;
 pub use synth::*;
 pub use synth_definition::*;
 
-pub type ScClientResult<T> = Result<T, failure::Error>;
+pub type ScClientResult<T> = Result<T, ScClientError>;
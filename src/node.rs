@@ -0,0 +1,125 @@
+use crate::{
+    types::{NodeValue, NodeValuesRange, OscType},
+    ScClientResult, Server,
+};
+
+/// Commands common for all the node types (synths and groups).
+///
+/// Controls can be specified either by index or by name.
+pub trait Node {
+    fn get_id(&self) -> i32;
+    fn get_server(&self) -> &Server;
+
+    /// Stops the node and removes it from its group. The node ID is returned to the server's
+    /// allocator for reuse, when the server notifies, that the node has ended (`/n_end`). So
+    /// the ID is reused only, if the server is
+    /// [sending notifications](struct.Server.html#method.set_receive_notifications).
+    fn free(&self) -> ScClientResult<&Self> {
+        let server = self.get_server();
+        // subscribed before the command, so the notification isn't missed
+        let subscription_id = server.free_node_id_on_end(self.get_id());
+        if let Err(e) = send_node_command(self, "/n_free", vec![]) {
+            server.node_watcher().unsubscribe(subscription_id);
+            return Err(e);
+        }
+        Ok(self)
+    }
+
+    /// Turns the node on or off. Off node doesn't process.
+    fn run(&self, is_running: bool) -> ScClientResult<&Self> {
+        send_node_command(self, "/n_run", vec![(is_running as i32).into()])?;
+        Ok(self)
+    }
+
+    /// Requests the server to send `/n_info` about the node. The reply is delivered to the
+    /// server's [`NodeWatcher`](struct.NodeWatcher.html) as
+    /// [`NodeEvent::Info`](enum.NodeEvent.html#variant.Info).
+    fn query(&self) -> ScClientResult<&Self> {
+        send_node_command(self, "/n_query", vec![])?;
+        Ok(self)
+    }
+
+    /// Sets control values. For a group it sets the controls of all the nodes in it.
+    fn set(&self, values: &[NodeValue]) -> ScClientResult<&Self> {
+        let args = values.iter().flat_map(Vec::from).collect();
+        send_node_command(self, "/n_set", args)?;
+        Ok(self)
+    }
+
+    /// Sets ranges of sequential control values.
+    fn set_range(&self, ranges: &[NodeValuesRange]) -> ScClientResult<&Self> {
+        let args = ranges.iter().flat_map(Vec::from).collect();
+        send_node_command(self, "/n_setn", args)?;
+        Ok(self)
+    }
+
+    /// Sets `number_of_controls` sequential controls, starting at `control`, to `value`.
+    fn fill(&self, fills: &[(OscType, i32, OscType)]) -> ScClientResult<&Self> {
+        let args = fills
+            .iter()
+            .flat_map(|(control, number_of_controls, value)| {
+                vec![control.clone(), (*number_of_controls).into(), value.clone()]
+            })
+            .collect();
+        send_node_command(self, "/n_fill", args)?;
+        Ok(self)
+    }
+
+    /// Maps controls to read from control buses. The second value of each
+    /// [`NodeValue`](types/struct.NodeValue.html) is the bus index. The index `-1` unmaps
+    /// the control.
+    fn map_to_control_bus(&self, mappings: &[NodeValue]) -> ScClientResult<&Self> {
+        let args = mappings.iter().flat_map(Vec::from).collect();
+        send_node_command(self, "/n_map", args)?;
+        Ok(self)
+    }
+
+    /// Maps ranges of sequential controls to read from sequential control buses. Each tuple is
+    /// `(control, bus_index, number_of_controls)`.
+    fn map_range_to_control_bus(&self, mappings: &[(OscType, i32, i32)]) -> ScClientResult<&Self> {
+        send_node_command(self, "/n_mapn", flatten_range_mappings(mappings))?;
+        Ok(self)
+    }
+
+    /// Maps controls to read from audio buses. The second value of each
+    /// [`NodeValue`](types/struct.NodeValue.html) is the bus index. The index `-1` unmaps
+    /// the control.
+    fn map_to_audio_bus(&self, mappings: &[NodeValue]) -> ScClientResult<&Self> {
+        let args = mappings.iter().flat_map(Vec::from).collect();
+        send_node_command(self, "/n_mapa", args)?;
+        Ok(self)
+    }
+
+    /// Maps ranges of sequential controls to read from sequential audio buses. Each tuple is
+    /// `(control, bus_index, number_of_controls)`.
+    fn map_range_to_audio_bus(&self, mappings: &[(OscType, i32, i32)]) -> ScClientResult<&Self> {
+        send_node_command(self, "/n_mapan", flatten_range_mappings(mappings))?;
+        Ok(self)
+    }
+}
+
+fn send_node_command<N: Node + ?Sized>(
+    node: &N,
+    command: &str,
+    mut args: Vec<OscType>,
+) -> ScClientResult<()> {
+    let mut send_args = vec![node.get_id().into()];
+    send_args.append(&mut args);
+    node.get_server()
+        .osc_server
+        .send_message(command, Some(send_args))?;
+    Ok(())
+}
+
+fn flatten_range_mappings(mappings: &[(OscType, i32, i32)]) -> Vec<OscType> {
+    mappings
+        .iter()
+        .flat_map(|(control, bus_index, number_of_controls)| {
+            vec![
+                control.clone(),
+                (*bus_index).into(),
+                (*number_of_controls).into(),
+            ]
+        })
+        .collect()
+}
@@ -0,0 +1,268 @@
+mod node_event_responder;
+#[cfg(test)]
+mod tests;
+pub use self::node_event_responder::NodeEventResponder;
+use crate::types::OscType;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+type Subscribers = RwLock<Vec<Subscriber>>;
+type NodeEventCallback = Arc<dyn Fn(&NodeEvent) + Send + Sync>;
+
+/// Keeps track of the nodes on the server by the notifications the server sends.
+///
+/// The server sends notifications only to the clients, which registered for them with
+/// [`Server::set_receive_notifications`](struct.Server.html#method.set_receive_notifications).
+#[derive(Clone)]
+pub struct NodeWatcher {
+    nodes: Arc<RwLock<HashMap<i32, NodeState>>>,
+    subscribers: Arc<Subscribers>,
+    next_subscription_id: Arc<AtomicUsize>,
+}
+
+impl NodeWatcher {
+    pub fn new() -> Self {
+        NodeWatcher {
+            nodes: Arc::new(RwLock::new(HashMap::new())),
+            subscribers: Arc::new(RwLock::new(Vec::new())),
+            next_subscription_id: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns responders for all the node notifications, which feed this watcher.
+    pub fn responders(&self) -> Vec<NodeEventResponder> {
+        NodeEvent::ADDRESSES
+            .iter()
+            .map(|address| NodeEventResponder::new(address, self.clone()))
+            .collect()
+    }
+
+    /// Calls `callback` on each event of the node with `node_id`. The subscription is removed
+    /// after the node has ended.
+    pub fn subscribe<F>(&self, node_id: i32, callback: F) -> usize
+    where
+        F: Fn(&NodeEvent) + Send + Sync + 'static,
+    {
+        self.add_subscriber(Some(node_id), Arc::new(callback))
+    }
+
+    /// Calls `callback` on each event of any node.
+    pub fn subscribe_all<F>(&self, callback: F) -> usize
+    where
+        F: Fn(&NodeEvent) + Send + Sync + 'static,
+    {
+        self.add_subscriber(None, Arc::new(callback))
+    }
+
+    pub fn unsubscribe(&self, subscription_id: usize) {
+        self.subscribers
+            .write()
+            .unwrap()
+            .retain(|subscriber| subscriber.id != subscription_id);
+    }
+
+    fn add_subscriber(&self, node_id: Option<i32>, callback: NodeEventCallback) -> usize {
+        let id = self.next_subscription_id.fetch_add(1, Ordering::SeqCst);
+        self.subscribers.write().unwrap().push(Subscriber {
+            id,
+            node_id,
+            callback,
+        });
+        id
+    }
+
+    /// Returns `true`, if the node is on the server, no matter if it's running or paused.
+    pub fn is_alive(&self, node_id: i32) -> bool {
+        self.nodes.read().unwrap().contains_key(&node_id)
+    }
+
+    /// Returns `true`, if the node is on the server and isn't paused.
+    pub fn is_playing(&self, node_id: i32) -> bool {
+        self.nodes
+            .read()
+            .unwrap()
+            .get(&node_id)
+            .is_some_and(|state| state.is_playing)
+    }
+
+    /// Returns last known info of the node, if it's alive.
+    pub fn get_node_info(&self, node_id: i32) -> Option<NodeInfo> {
+        self.nodes
+            .read()
+            .unwrap()
+            .get(&node_id)
+            .map(|state| state.info.clone())
+    }
+
+    pub fn get_alive_node_ids(&self) -> Vec<i32> {
+        self.nodes.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Forgets all the nodes. Should be called, when the server is restarted.
+    pub fn clear(&self) {
+        self.nodes.write().unwrap().clear();
+    }
+
+    pub(crate) fn on_event(&self, event: NodeEvent) {
+        self.update_nodes(&event);
+
+        let node_id = event.get_info().id;
+        let is_ended = matches!(event, NodeEvent::End(_));
+        // callbacks are called without holding the lock, so they can (un)subscribe
+        let callbacks: Vec<NodeEventCallback> = self
+            .subscribers
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|subscriber| subscriber.node_id.is_none_or(|id| id == node_id))
+            .map(|subscriber| subscriber.callback.clone())
+            .collect();
+        for callback in callbacks {
+            callback(&event);
+        }
+        if is_ended {
+            self.subscribers
+                .write()
+                .unwrap()
+                .retain(|subscriber| subscriber.node_id != Some(node_id));
+        }
+    }
+
+    fn update_nodes(&self, event: &NodeEvent) {
+        let mut nodes = self.nodes.write().unwrap();
+        match event {
+            NodeEvent::End(info) => {
+                nodes.remove(&info.id);
+            }
+            NodeEvent::Off(info) => {
+                nodes.insert(info.id, NodeState::new(info.clone(), false));
+            }
+            NodeEvent::Go(info) | NodeEvent::On(info) => {
+                nodes.insert(info.id, NodeState::new(info.clone(), true));
+            }
+            NodeEvent::Move(info) | NodeEvent::Info(info) => {
+                let is_playing = nodes.get(&info.id).is_none_or(|state| state.is_playing);
+                nodes.insert(info.id, NodeState::new(info.clone(), is_playing));
+            }
+        }
+    }
+}
+
+impl Default for NodeWatcher {
+    fn default() -> Self {
+        NodeWatcher::new()
+    }
+}
+
+struct Subscriber {
+    id: usize,
+    node_id: Option<i32>,
+    callback: NodeEventCallback,
+}
+
+struct NodeState {
+    info: NodeInfo,
+    is_playing: bool,
+}
+
+impl NodeState {
+    fn new(info: NodeInfo, is_playing: bool) -> Self {
+        NodeState { info, is_playing }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeEvent {
+    /// a node was started (`/n_go`).
+    Go(NodeInfo),
+    /// a node ended (`/n_end`).
+    End(NodeInfo),
+    /// a node was turned off (`/n_off`).
+    Off(NodeInfo),
+    /// a node was turned on (`/n_on`).
+    On(NodeInfo),
+    /// a node was moved (`/n_move`).
+    Move(NodeInfo),
+    /// a reply to `/n_query` (`/n_info`).
+    Info(NodeInfo),
+}
+
+impl NodeEvent {
+    const ADDRESSES: [&'static str; 6] =
+        ["/n_go", "/n_end", "/n_off", "/n_on", "/n_move", "/n_info"];
+
+    /// Decodes the notification message. Returns `None` if the address is not a node
+    /// notification or the arguments are malformed.
+    pub fn from_message(address: &str, args: &[OscType]) -> Option<Self> {
+        let info = NodeInfo::from_args(args)?;
+        match address {
+            "/n_go" => Some(NodeEvent::Go(info)),
+            "/n_end" => Some(NodeEvent::End(info)),
+            "/n_off" => Some(NodeEvent::Off(info)),
+            "/n_on" => Some(NodeEvent::On(info)),
+            "/n_move" => Some(NodeEvent::Move(info)),
+            "/n_info" => Some(NodeEvent::Info(info)),
+            _ => None,
+        }
+    }
+
+    pub fn get_info(&self) -> &NodeInfo {
+        match self {
+            NodeEvent::Go(info)
+            | NodeEvent::End(info)
+            | NodeEvent::Off(info)
+            | NodeEvent::On(info)
+            | NodeEvent::Move(info)
+            | NodeEvent::Info(info) => info,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeInfo {
+    /// node ID.
+    pub id: i32,
+    /// the node's parent group ID.
+    pub parent_group_id: i32,
+    /// previous node ID, -1 if no previous node.
+    pub previous_node_id: i32,
+    /// next node ID, -1 if no next node.
+    pub next_node_id: i32,
+    pub kind: NodeKind,
+}
+
+impl NodeInfo {
+    fn from_args(args: &[OscType]) -> Option<Self> {
+        let int_at = |index: usize| match args.get(index) {
+            Some(OscType::Int(n)) => Some(*n),
+            _ => None,
+        };
+        let kind = if int_at(4)? == 1 {
+            NodeKind::Group {
+                head_node_id: int_at(5)?,
+                tail_node_id: int_at(6)?,
+            }
+        } else {
+            NodeKind::Synth
+        };
+        Some(NodeInfo {
+            id: int_at(0)?,
+            parent_group_id: int_at(1)?,
+            previous_node_id: int_at(2)?,
+            next_node_id: int_at(3)?,
+            kind,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeKind {
+    Synth,
+    Group {
+        /// the ID of the head node, -1 if there is no head node.
+        head_node_id: i32,
+        /// the ID of the tail node, -1 if there is no tail node.
+        tail_node_id: i32,
+    },
+}
@@ -0,0 +1,36 @@
+use super::{NodeEvent, NodeWatcher};
+use crate::{types::OscMessage, AfterCallAction, OscResponder, ScClientResult};
+use log::warn;
+
+pub struct NodeEventResponder {
+    address: String,
+    node_watcher: NodeWatcher,
+}
+
+impl NodeEventResponder {
+    pub fn new(address: &str, node_watcher: NodeWatcher) -> Self {
+        NodeEventResponder {
+            address: address.to_string(),
+            node_watcher,
+        }
+    }
+}
+
+impl OscResponder for NodeEventResponder {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        let args = message.args.clone().unwrap_or_default();
+        match NodeEvent::from_message(&message.addr, &args) {
+            Some(event) => self.node_watcher.on_event(event),
+            None => warn!("Can't decode node notification: {:?}", message),
+        }
+        Ok(())
+    }
+
+    fn get_address(&self) -> String {
+        self.address.clone()
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        AfterCallAction::Reschedule
+    }
+}
@@ -0,0 +1,99 @@
+use super::*;
+use std::sync::Mutex;
+
+fn synth_info(id: i32) -> Vec<OscType> {
+    vec![id.into(), 1.into(), (-1).into(), (-1).into(), 0.into()]
+}
+
+#[test]
+fn decode_node_event() {
+    assert_eq!(
+        Some(NodeEvent::Go(NodeInfo {
+            id: 1000,
+            parent_group_id: 1,
+            previous_node_id: -1,
+            next_node_id: -1,
+            kind: NodeKind::Synth,
+        })),
+        NodeEvent::from_message("/n_go", &synth_info(1000))
+    );
+
+    let group_args: Vec<OscType> = vec![
+        1001.into(),
+        0.into(),
+        1000.into(),
+        (-1).into(),
+        1.into(),
+        1002.into(),
+        1003.into(),
+    ];
+    assert_eq!(
+        Some(NodeEvent::Move(NodeInfo {
+            id: 1001,
+            parent_group_id: 0,
+            previous_node_id: 1000,
+            next_node_id: -1,
+            kind: NodeKind::Group {
+                head_node_id: 1002,
+                tail_node_id: 1003,
+            },
+        })),
+        NodeEvent::from_message("/n_move", &group_args)
+    );
+
+    assert_eq!(None, NodeEvent::from_message("/n_foo", &synth_info(1000)));
+    assert_eq!(None, NodeEvent::from_message("/n_go", &group_args[..5]));
+}
+
+#[test]
+fn track_node_state() {
+    let watcher = NodeWatcher::new();
+    let on_event = |address: &str| {
+        watcher.on_event(NodeEvent::from_message(address, &synth_info(1000)).unwrap())
+    };
+
+    on_event("/n_go");
+    assert!(watcher.is_alive(1000));
+    assert!(watcher.is_playing(1000));
+
+    on_event("/n_off");
+    assert!(watcher.is_alive(1000));
+    assert!(!watcher.is_playing(1000));
+
+    on_event("/n_move");
+    assert!(!watcher.is_playing(1000));
+
+    on_event("/n_on");
+    assert!(watcher.is_playing(1000));
+
+    on_event("/n_end");
+    assert!(!watcher.is_alive(1000));
+    assert!(watcher.get_alive_node_ids().is_empty());
+}
+
+#[test]
+fn notify_subscribers() {
+    let watcher = NodeWatcher::new();
+    let node_events = Arc::new(Mutex::new(Vec::new()));
+    let all_events = Arc::new(Mutex::new(Vec::new()));
+
+    let events = node_events.clone();
+    watcher.subscribe(1000, move |event| {
+        events.lock().unwrap().push(event.get_info().id)
+    });
+    let events = all_events.clone();
+    let subscription_id =
+        watcher.subscribe_all(move |event| events.lock().unwrap().push(event.get_info().id));
+
+    watcher.on_event(NodeEvent::from_message("/n_go", &synth_info(1000)).unwrap());
+    watcher.on_event(NodeEvent::from_message("/n_go", &synth_info(1001)).unwrap());
+    watcher.on_event(NodeEvent::from_message("/n_end", &synth_info(1000)).unwrap());
+    // the subscription for the node is removed after it has ended
+    watcher.on_event(NodeEvent::from_message("/n_go", &synth_info(1000)).unwrap());
+
+    watcher.unsubscribe(subscription_id);
+    watcher.on_event(NodeEvent::from_message("/n_end", &synth_info(1001)).unwrap());
+
+    assert_eq!(vec![1000, 1000], *node_events.lock().unwrap());
+    assert_eq!(vec![1000, 1001, 1000, 1000], *all_events.lock().unwrap());
+}
@@ -1,105 +1,209 @@
-use crate::ScClientResult;
-use failure::Fail;
-use log::{debug, error, warn};
-use rosc::{decoder, encoder, OscBundle, OscError, OscMessage, OscPacket, OscType};
-use std::net::{SocketAddr, SocketAddrV4, UdpSocket};
-use std::str::FromStr;
-use std::sync::{Arc, RwLock};
-use std::thread;
-use std::thread::Thread;
-
-type Responders = RwLock<Vec<Box<OscResponder>>>;
+mod failure_responder;
+#[cfg(test)]
+mod tests;
+mod transport;
+use self::failure_responder::{FailureResponder, Failures};
+#[cfg(test)]
+pub(crate) use self::transport::spawn_tcp_server;
+pub use self::transport::{TcpTransport, Transport, UdpTransport, MAX_TCP_PACKET_SIZE};
+use crate::{types::OscTime, ScClientError, ScClientResult, ServerFailure};
+use log::{debug, error};
+use rosc::{decoder, encoder, OscBundle, OscMessage, OscPacket, OscType};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+/// Responders with their IDs.
+type Responders = RwLock<Vec<(usize, Box<dyn OscResponder>)>>;
+type PendingSyncs = Mutex<HashMap<i32, Box<dyn FnOnce(Vec<ServerFailure>) + Send>>>;
+type ErrorCallback = Arc<dyn Fn(&ScClientError) + Send + Sync>;
+
+/// How long [`OscServer::sync`](struct.OscServer.html#method.sync) waits for the reply.
+pub const DEFAULT_SYNC_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct OscServer {
-    pub client_address: SocketAddrV4,
-    pub server_address: SocketAddrV4,
-    udp_socket: Arc<UdpSocket>,
+    transport: Arc<dyn Transport>,
     responders: Arc<Responders>,
-    sync_uid: i32,
+    next_responder_id: AtomicUsize,
+    sync_uid: AtomicI32,
+    request_lock: Mutex<()>,
+    pending_syncs: Arc<PendingSyncs>,
+    failures: Arc<Failures>,
+    error_callback: Arc<RwLock<Option<ErrorCallback>>>,
+    bundle_packets: Mutex<HashMap<ThreadId, Vec<OscPacket>>>,
 }
 
 impl OscServer {
-    //! The addresses are in `ip:port` format.
-    pub fn new(client_address: &str, server_address: &str) -> Self {
-        let client_addr = SocketAddrV4::from_str(client_address).expect(&format!(
-            "Error init client SocketAddrV4 from string {}",
-            client_address
-        ));
-        let server_addr = SocketAddrV4::from_str(server_address).expect(&format!(
-            "Error init server SocketAddrV4 from string {}",
-            server_address
-        ));
-        let socket = UdpSocket::bind(client_address).expect(&format!(
-            "Cannot bind UdpSocket to address: {}",
-            client_address
-        ));
+    /// Creates the server, which talks to the server over UDP. The addresses are in `ip:port`
+    /// format.
+    pub fn new(client_address: &str, server_address: &str) -> ScClientResult<Self> {
+        let transport = UdpTransport::new(client_address, server_address)?;
+        Ok(OscServer::with_transport(transport))
+    }
+
+    /// Creates the server, which talks to the server over TCP. The address is in `ip:port`
+    /// format. The session password is sent first on each connection.
+    pub fn new_tcp(server_address: &str, session_password: Option<&str>) -> ScClientResult<Self> {
+        let transport = TcpTransport::with_session_password(server_address, session_password)?;
+        Ok(OscServer::with_transport(transport))
+    }
+
+    pub fn with_transport<T: Transport>(transport: T) -> Self {
         let mut osc_server = OscServer {
-            client_address: client_addr,
-            server_address: server_addr,
-            udp_socket: Arc::new(socket),
+            transport: Arc::new(transport),
             responders: Arc::new(RwLock::new(Vec::new())),
-            sync_uid: 0,
+            next_responder_id: AtomicUsize::new(0),
+            sync_uid: AtomicI32::new(0),
+            request_lock: Mutex::new(()),
+            pending_syncs: Arc::new(Mutex::new(HashMap::new())),
+            failures: Arc::new(Failures::default()),
+            error_callback: Arc::new(RwLock::new(None)),
+            bundle_packets: Mutex::new(HashMap::new()),
         };
-        osc_server.init_sync_responder();
+        osc_server.init_internal_responders();
         osc_server.start_listener();
 
         osc_server
     }
 
-    fn init_sync_responder(&mut self) {
-        // we can't process it in on_message, because we need current thread, which is out
-        // of the on_message context
-        let sync_responder = SyncResponder::new();
-        self.responders
-            .write()
-            .expect("can't write responder")
-            .push(Box::new(sync_responder));
+    fn init_internal_responders(&mut self) {
+        // the sync responder is added directly, because the address is reserved for it
+        let sync_responder = SyncResponder::new(self.pending_syncs.clone(), self.failures.clone());
+        let failure_responder = FailureResponder::new(self.failures.clone());
+        let mut responders = self.responders.write().unwrap();
+        responders.push((self.next_responder_id(), Box::new(sync_responder)));
+        responders.push((self.next_responder_id(), Box::new(failure_responder)));
+    }
+
+    /// Waits until the server has processed all the previous commands, but not longer than
+    /// [`DEFAULT_SYNC_TIMEOUT`](constant.DEFAULT_SYNC_TIMEOUT.html).
+    pub fn sync(&self) -> ScClientResult<&Self> {
+        self.sync_timeout(DEFAULT_SYNC_TIMEOUT)
+    }
+
+    /// Waits until the server has processed all the previous commands. Fails with
+    /// [`ScClientError::SyncTimeout`](enum.ScClientError.html), if the server hasn't replied
+    /// in `timeout`, or with [`ScClientError::ServerFailure`](enum.ScClientError.html), if a
+    /// command has failed since the previous sync and the failure wasn't reported otherwise.
+    /// The first failure is returned, if there are several.
+    pub fn sync_timeout(&self, timeout: Duration) -> ScClientResult<&Self> {
+        let (sender, receiver) = mpsc::channel();
+        let sync_id = self.send_sync(move |failures| {
+            // the receiver is gone, if the sync has timed out already
+            let _ = sender.send(failures);
+        })?;
+        let result = receiver.recv_timeout(timeout);
+        self.cancel_sync(sync_id);
+        match result {
+            Ok(failures) => match failures.into_iter().next() {
+                Some(failure) => Err(ScClientError::ServerFailure(failure)),
+                None => Ok(self),
+            },
+            Err(_) => Err(ScClientError::SyncTimeout(timeout)),
+        }
+    }
+
+    /// Sends `/sync` and calls `on_synced` on the reply with the unreported failures since
+    /// the previous sync. Returns the ID of the sync.
+    pub(crate) fn send_sync<F>(&self, on_synced: F) -> ScClientResult<i32>
+    where
+        F: FnOnce(Vec<ServerFailure>) + Send + 'static,
+    {
+        self.send_with_sync(|| Ok(()), on_synced)
+    }
+
+    /// Calls `send` and sends `/sync` right after the messages, which `send` sends, so the
+    /// failures, which `on_synced` is called with, include the failures of these messages, but
+    /// not of the messages, which are sent with this method by the other threads afterwards.
+    /// Returns the ID of the sync.
+    pub(crate) fn send_with_sync<S, F>(&self, send: S, on_synced: F) -> ScClientResult<i32>
+    where
+        S: FnOnce() -> ScClientResult<()>,
+        F: FnOnce(Vec<ServerFailure>) + Send + 'static,
+    {
+        let _request = self
+            .request_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        send()?;
+        let sync_id = self.sync_uid.fetch_add(1, Ordering::SeqCst) + 1;
+        self.pending_syncs
+            .lock()
+            .unwrap()
+            .insert(sync_id, Box::new(on_synced));
+
+        // sync is never put into a bundle, otherwise it would wait forever
+        let sent = self.send_packet_immediately(&OscPacket::Message(OscMessage {
+            addr: String::from("/sync"),
+            args: Some(vec![sync_id.into()]),
+        }));
+        if let Err(e) = sent {
+            self.cancel_sync(sync_id);
+            return Err(e);
+        }
+        Ok(sync_id)
+    }
+
+    #[cfg(all(test, feature = "tokio"))]
+    pub(crate) fn has_pending_syncs(&self) -> bool {
+        !self.pending_syncs.lock().unwrap().is_empty()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn num_of_responders(&self) -> usize {
+        self.responders.read().unwrap().len()
+    }
+
+    /// Forgets the sync, so its callback isn't called anymore.
+    pub(crate) fn cancel_sync(&self, sync_id: i32) {
+        self.pending_syncs.lock().unwrap().remove(&sync_id);
     }
 
-    pub fn sync(&mut self) -> ScClientResult<&Self> {
-        self.sync_uid += 1;
-        self.send_message("/sync", Some(vec![self.sync_uid.into()]))?;
-        thread::park();
-        Ok(self)
+    /// Sets the callback for the errors, which happen in the background, while receiving and
+    /// dispatching the server's replies. The errors are logged anyway.
+    pub fn set_error_callback<F>(&self, callback: F)
+    where
+        F: Fn(&ScClientError) + Send + Sync + 'static,
+    {
+        *self.error_callback.write().unwrap() = Some(Arc::new(callback));
+    }
+
+    /// Logs the error, which happened in the background, and passes it to the error callback.
+    pub(crate) fn report_error(&self, error: &ScClientError) {
+        error!("{}", error);
+        OscServer::call_error_callback(&self.error_callback, error);
+    }
+
+    fn call_error_callback(error_callback: &RwLock<Option<ErrorCallback>>, error: &ScClientError) {
+        // the callback is cloned, so it can replace itself
+        let callback = error_callback.read().unwrap().clone();
+        if let Some(callback) = callback {
+            callback(error);
+        }
     }
 
     fn start_listener(&self) {
-        let socket = self.udp_socket.clone();
-        let server_address = self.server_address.clone();
+        let transport = self.transport.clone();
         let mut responders = self.responders.clone();
-        thread::spawn(move || {
-            let mut buf = [0u8; rosc::decoder::MTU];
-            loop {
-                match socket.recv_from(&mut buf) {
-                    Ok((size, addr)) => OscServer::on_receive_packet(
-                        &addr,
-                        &buf,
-                        size,
-                        &server_address,
-                        &mut responders,
-                    )
-                    .expect("unexpected OSC error"),
-                    Err(e) => error!("Error receiving from socket: {}", e),
-                }
+        let error_callback = self.error_callback.clone();
+        thread::spawn(move || loop {
+            let result = transport
+                .receive()
+                .and_then(|buf| OscServer::on_receive_packet(&buf, &mut responders));
+            if let Err(e) = result {
+                error!("Error handling packet from the server: {}", e);
+                OscServer::call_error_callback(&error_callback, &e);
             }
         });
     }
 
-    fn on_receive_packet(
-        address: &SocketAddr,
-        buf: &[u8],
-        size: usize,
-        server_address: &SocketAddrV4,
-        responders: &mut Arc<Responders>,
-    ) -> ScClientResult<()> {
-        if *address != SocketAddr::from(*server_address) {
-            return Ok(warn!("Reject packet from unknow host: {}", address));
-        }
-
-        match decoder::decode(&buf[..size]) {
-            Ok(packet) => OscServer::handle_packet(packet, responders),
-            Err(e) => Err(OscServerError::DecodePacket(e).into()),
-        }
+    fn on_receive_packet(buf: &[u8], responders: &mut Arc<Responders>) -> ScClientResult<()> {
+        let packet = decoder::decode(buf).map_err(ScClientError::Decode)?;
+        OscServer::handle_packet(packet, responders)
     }
 
     fn handle_packet(packet: OscPacket, responders: &mut Arc<Responders>) -> ScClientResult<()> {
@@ -112,14 +216,23 @@ impl OscServer {
     fn on_message(message: OscMessage, responders: &mut Arc<Responders>) -> ScClientResult<()> {
         match message.addr.as_ref() {
             "/done" => OscServer::on_done_message(&message, responders),
-            "/fail" => OscServer::on_fail_message(&message),
+            "/fail" => OscServer::on_fail_message(&message, responders),
             _ => OscServer::call_responders_for_key(&message.addr, &message, responders),
         }
     }
 
-    fn on_bundle(bundle: OscBundle, _responders: &mut Arc<Responders>) -> ScClientResult<()> {
-        debug!("OSC Bundle: {:?}", bundle);
-        Ok(())
+    /// Dispatches the bundle's content in order, the nested bundles are unpacked recursively.
+    /// All the packets are handled, even if some of them fail, and the first error is returned.
+    fn on_bundle(bundle: OscBundle, responders: &mut Arc<Responders>) -> ScClientResult<()> {
+        debug!("OSC Bundle with timetag: {:?}", bundle.timetag);
+        let mut result = Ok(());
+        for packet in bundle.content {
+            let packet_result = OscServer::handle_packet(packet, responders);
+            if result.is_ok() {
+                result = packet_result;
+            }
+        }
+        result
     }
 
     fn on_done_message(
@@ -127,14 +240,14 @@ impl OscServer {
         responders: &mut Arc<Responders>,
     ) -> ScClientResult<()> {
         debug!("get /done message: {:?}", message);
-        match message.args.as_ref() {
-            Some(args) => {
-                if let OscType::String(key) = args.clone().remove(0) {
-                    return OscServer::call_responders_for_key(&key, message, responders);
-                };
+        match message.args.as_ref().and_then(|args| args.first()) {
+            Some(OscType::String(key)) => {
+                OscServer::call_responders_for_key(key, message, responders)
+            }
+            _ => {
+                debug!("Got /done message, but without the command");
                 Ok(())
             }
-            None => return Ok(debug!("Got /done message, but without any args")),
         }
     }
 
@@ -143,56 +256,103 @@ impl OscServer {
         message: &OscMessage,
         responders: &mut Arc<Responders>,
     ) -> ScClientResult<()> {
+        let mut result = Ok(());
         responders
             .write()
-            .map_err(|e| OscServerError::CallResponder(String::new()))?
-            .retain(|ref responder| {
-                if responder.get_address() == key {
-                    responder.callback(message).unwrap();
-                    return responder.get_after_call_action(message) == AfterCallAction::Reschedule;
+            .map_err(|e| ScClientError::Responder(e.to_string()))?
+            .retain(|(_, responder)| {
+                if responder.get_address() != key {
+                    return true;
+                }
+                // a panic would poison the lock and stop the listener, so it's turned into an
+                // error, and the responder is removed
+                let call = panic::catch_unwind(AssertUnwindSafe(|| {
+                    let call_result = responder.callback(message);
+                    (call_result, responder.get_after_call_action(message))
+                }));
+                let (call_result, after_call_action) = call.unwrap_or_else(|_| {
+                    let e = ScClientError::Responder(format!("Responder for {} panicked", key));
+                    (Err(e), AfterCallAction::None)
+                });
+                // the other responders are called, even if one of them fails
+                if let Err(e) = call_result {
+                    if result.is_ok() {
+                        result = Err(e);
+                    } else {
+                        error!("Error call responder for {}: {}", key, e);
+                    }
                 }
-                true
+                after_call_action == AfterCallAction::Reschedule
             });
 
-        Ok(())
+        result
     }
 
-    fn on_fail_message(message: &OscMessage) -> ScClientResult<()> {
-        if let Some(args) = message.args.as_ref() {
-            if let OscType::String(addr) = args.clone().remove(0) {
-                error!("Server responses with error:\n\t{}, ", addr);
-            }
-            if let OscType::String(error) = args.clone().remove(1) {
-                println!("{}", error);
-            };
-        };
-        Ok(error!("Server responses with /fail message"))
+    /// Logs the failure and passes it to the responders for `/fail`. The responders for the
+    /// failed command don't get it, because they expect only its replies. The failures are
+    /// reported by [the syncs](#method.sync) instead.
+    fn on_fail_message(
+        message: &OscMessage,
+        responders: &mut Arc<Responders>,
+    ) -> ScClientResult<()> {
+        match ServerFailure::from_message(message) {
+            Some(failure) => error!("{}", failure),
+            None => error!("Server responses with /fail message: {:?}", message.args),
+        }
+        OscServer::call_responders_for_key("/fail", message, responders)
     }
 
     /// Adds [`OscResponder`](trait.OscResponder.html) to perform on getting message to address.
-    pub fn add_responder<T: OscResponder>(&self, responder: T) -> ScClientResult<()> {
+    /// Returns the ID of the responder, which it can be removed by with
+    /// [`remove_responder`](#method.remove_responder).
+    pub fn add_responder<T: OscResponder>(&self, responder: T) -> ScClientResult<usize> {
         if responder.get_address() == "/synced" {
-            return Err(OscServerError::AddResponder(
-                "can't add responder for reserved address".to_string(),
-            )
-            .into());
+            return Err(ScClientError::ReservedAddress(responder.get_address()));
         }
 
-        Ok(self
-            .responders
+        let id = self.next_responder_id();
+        self.responders
+            .write()
+            .map_err(|e| ScClientError::Responder(e.to_string()))?
+            .push((id, Box::new(responder)));
+        Ok(id)
+    }
+
+    /// Removes the responder, if it's still there.
+    pub fn remove_responder(&self, responder_id: usize) -> ScClientResult<()> {
+        self.responders
             .write()
-            .map_err(|e| OscServerError::AddResponder(format!("{}", e)))?
-            .push(Box::new(responder)))
+            .map_err(|e| ScClientError::Responder(e.to_string()))?
+            .retain(|(id, _)| *id != responder_id);
+        Ok(())
     }
 
-    pub fn remove_responders_for_address(&mut self, address: &str) -> ScClientResult<()> {
-        Ok(self
-            .responders
+    pub fn remove_responders_for_address(&self, address: &str) -> ScClientResult<()> {
+        self.responders
             .write()
-            .map_err(|e| OscServerError::RemoveResponder(format!("{}", e)))?
-            .retain(|ref responder| responder.get_address() != address.to_string()))
+            .map_err(|e| ScClientError::Responder(e.to_string()))?
+            .retain(|(_, responder)| responder.get_address() != address);
+        Ok(())
     }
 
+    fn next_responder_id(&self) -> usize {
+        self.next_responder_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Maximum number of arguments (all are 4 bytes long) in a message to `address`, which fits
+    /// into a single packet.
+    pub fn max_num_of_args(address: &str) -> usize {
+        let padded_size = |size: usize| size.div_ceil(4) * 4;
+        let address_size = padded_size(address.len() + 1);
+        let mut num_of_args = (decoder::MTU - address_size) / 4;
+        // the type tag string is a comma, a tag per argument and a null terminator
+        while address_size + padded_size(num_of_args + 2) + num_of_args * 4 > decoder::MTU {
+            num_of_args -= 1;
+        }
+        num_of_args
+    }
+
+    /// Sends the message or, if a bundle is started, adds the message to the bundle.
     pub fn send_message(
         &self,
         address: &str,
@@ -202,27 +362,142 @@ impl OscServer {
             addr: address.to_string(),
             args: arguments,
         };
-        let msg_buf: Vec<u8> = encoder::encode(&OscPacket::Message(message))
-            .map_err(|e| OscServerError::SendMessage(e))?;
-        Ok(self.udp_socket.send_to(&msg_buf, self.server_address)?)
+        self.send_packet(OscPacket::Message(message))
+    }
+
+    /// Sends the bundle of packets, which should be executed by the server at `time`.
+    pub fn send_bundle(&self, time: OscTime, packets: Vec<OscPacket>) -> ScClientResult<usize> {
+        let bundle = OscBundle {
+            timetag: time.into(),
+            content: packets,
+        };
+        self.send_packet(OscPacket::Bundle(bundle))
+    }
+
+    /// Starts collecting all the messages, which are sent from the current thread, into a
+    /// bundle, instead of sending them. The bundle is sent with
+    /// [`finish_bundle`](#method.finish_bundle).
+    pub fn start_bundle(&self) -> ScClientResult<()> {
+        let mut bundle_packets = self.bundle_packets.lock().unwrap();
+        let thread_id = thread::current().id();
+        if bundle_packets.contains_key(&thread_id) {
+            return Err(ScClientError::BundleAlreadyStarted);
+        }
+        bundle_packets.insert(thread_id, Vec::new());
+        Ok(())
+    }
+
+    /// Sends the bundle, started in the current thread, which should be executed by the server
+    /// at `time`.
+    pub fn finish_bundle(&self, time: OscTime) -> ScClientResult<usize> {
+        let packets = self
+            .bundle_packets
+            .lock()
+            .unwrap()
+            .remove(&thread::current().id())
+            .ok_or(ScClientError::BundleNotStarted)?;
+        self.send_bundle(time, packets)
+    }
+
+    /// Drops the bundle, started in the current thread, without sending it.
+    pub fn cancel_bundle(&self) {
+        self.bundle_packets
+            .lock()
+            .unwrap()
+            .remove(&thread::current().id());
+    }
+
+    fn send_packet(&self, packet: OscPacket) -> ScClientResult<usize> {
+        let mut bundle_packets = self.bundle_packets.lock().unwrap();
+        if let Some(packets) = bundle_packets.get_mut(&thread::current().id()) {
+            packets.push(packet);
+            return Ok(0);
+        }
+        drop(bundle_packets);
+        self.send_packet_immediately(&packet)
     }
+
+    fn send_packet_immediately(&self, packet: &OscPacket) -> ScClientResult<usize> {
+        let msg_buf: Vec<u8> = encoder::encode(packet).map_err(ScClientError::Encode)?;
+        self.transport.send(&msg_buf)
+    }
+}
+
+/// Request, which is waited for. Its responder is removed and its sync is cancelled on drop,
+/// so they aren't left behind, if the request fails, times out or its future is dropped.
+pub(crate) struct PendingRequest {
+    osc_server: Arc<OscServer>,
+    responder_id: Option<usize>,
+    sync_id: Option<i32>,
 }
 
+impl PendingRequest {
+    pub fn new(osc_server: Arc<OscServer>) -> Self {
+        PendingRequest {
+            osc_server,
+            responder_id: None,
+            sync_id: None,
+        }
+    }
+
+    pub fn add_responder<T: OscResponder>(&mut self, responder: T) -> ScClientResult<()> {
+        self.responder_id = Some(self.osc_server.add_responder(responder)?);
+        Ok(())
+    }
+
+    /// Calls `send` and syncs right after the messages, which it sends. See
+    /// [`OscServer::send_with_sync`](struct.OscServer.html#method.send_with_sync).
+    pub fn send_with_sync<S, F>(&mut self, send: S, on_synced: F) -> ScClientResult<()>
+    where
+        S: FnOnce(&OscServer) -> ScClientResult<()>,
+        F: FnOnce(Vec<ServerFailure>) + Send + 'static,
+    {
+        let osc_server = &self.osc_server;
+        self.sync_id = Some(osc_server.send_with_sync(|| send(osc_server), on_synced)?);
+        Ok(())
+    }
+}
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        if let Some(responder_id) = self.responder_id {
+            if let Err(e) = self.osc_server.remove_responder(responder_id) {
+                error!("Error remove responder: {}", e);
+            }
+        }
+        if let Some(sync_id) = self.sync_id {
+            self.osc_server.cancel_sync(sync_id);
+        }
+    }
+}
+
+/// Calls back the pending sync, which waits for the `/synced` with the same ID.
 struct SyncResponder {
-    thread: Thread,
+    pending_syncs: Arc<PendingSyncs>,
+    failures: Arc<Failures>,
 }
 
 impl SyncResponder {
-    pub fn new() -> Self {
+    pub fn new(pending_syncs: Arc<PendingSyncs>, failures: Arc<Failures>) -> Self {
         SyncResponder {
-            thread: thread::current(),
+            pending_syncs,
+            failures,
         }
     }
 }
 
 impl OscResponder for SyncResponder {
-    fn callback(&self, _message: &OscMessage) -> ScClientResult<()> {
-        Ok(self.thread.unpark())
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        if let Some(OscType::Int(sync_id)) = message.args.as_ref().and_then(|args| args.first()) {
+            // the failures, reported before the reply, belong to this sync, even if it's gone
+            let failures = self.failures.take_unreported();
+            // the callback is called without holding the lock, so it can sync again
+            let on_synced = self.pending_syncs.lock().unwrap().remove(sync_id);
+            if let Some(on_synced) = on_synced {
+                on_synced(failures);
+            }
+        }
+        Ok(())
     }
 
     fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
@@ -245,17 +520,3 @@ pub enum AfterCallAction {
     None,
     Reschedule,
 }
-
-#[derive(Fail, Debug)]
-enum OscServerError {
-    #[fail(display = "Error decode packet: {:?}", _0)]
-    DecodePacket(OscError),
-    #[fail(display = "Error add responder: {}", _0)]
-    AddResponder(String),
-    #[fail(display = "Error call responder: {}", _0)]
-    CallResponder(String),
-    #[fail(display = "Error remove responder: {}", _0)]
-    RemoveResponder(String),
-    #[fail(display = "Error send message: {:?}", _0)]
-    SendMessage(OscError),
-}
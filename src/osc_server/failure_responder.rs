@@ -0,0 +1,48 @@
+use crate::{types::OscMessage, AfterCallAction, OscResponder, ScClientResult, ServerFailure};
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// Failures of the server, which weren't reported by a sync yet.
+#[derive(Default)]
+pub struct Failures {
+    unreported: Mutex<Vec<ServerFailure>>,
+}
+
+impl Failures {
+    /// Returns the failures in the order of arrival since the previous call.
+    pub fn take_unreported(&self) -> Vec<ServerFailure> {
+        mem::take(&mut *self.unreported.lock().unwrap())
+    }
+
+    fn on_failure(&self, failure: ServerFailure) {
+        self.unreported.lock().unwrap().push(failure);
+    }
+}
+
+/// Receives all the `/fail` messages and passes them to [`Failures`](struct.Failures.html).
+pub struct FailureResponder {
+    failures: Arc<Failures>,
+}
+
+impl FailureResponder {
+    pub fn new(failures: Arc<Failures>) -> Self {
+        FailureResponder { failures }
+    }
+}
+
+impl OscResponder for FailureResponder {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        if let Some(failure) = ServerFailure::from_message(message) {
+            self.failures.on_failure(failure);
+        }
+        Ok(())
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        AfterCallAction::Reschedule
+    }
+
+    fn get_address(&self) -> String {
+        String::from("/fail")
+    }
+}
@@ -0,0 +1,278 @@
+use super::*;
+
+struct RecordingResponder {
+    address: String,
+    received: Arc<Mutex<Vec<OscMessage>>>,
+}
+
+impl OscResponder for RecordingResponder {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        self.received.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        AfterCallAction::Reschedule
+    }
+
+    fn get_address(&self) -> String {
+        self.address.clone()
+    }
+}
+
+fn message(address: &str, args: Vec<OscType>) -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: address.to_string(),
+        args: Some(args),
+    })
+}
+
+fn bundle(content: Vec<OscPacket>) -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATELY.into(),
+        content,
+    })
+}
+
+#[test]
+fn handle_packet_dispatches_nested_bundles() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let mut responders: Arc<Responders> = Arc::new(RwLock::new(Vec::new()));
+    for address in &["/n_go", "/b_alloc"] {
+        responders.write().unwrap().push((
+            0,
+            Box::new(RecordingResponder {
+                address: address.to_string(),
+                received: received.clone(),
+            }),
+        ));
+    }
+
+    let packet = bundle(vec![
+        message("/n_go", vec![1000.into()]),
+        bundle(vec![
+            message("/done", vec!["/b_alloc".into(), 1.into()]),
+            bundle(vec![message(
+                "/fail",
+                vec!["/s_new".into(), "error".into()],
+            )]),
+        ]),
+        message("/unknown", vec![]),
+    ]);
+    OscServer::handle_packet(packet, &mut responders).unwrap();
+
+    let addresses: Vec<String> = received
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|message| message.addr.clone())
+        .collect();
+    assert_eq!(vec!["/n_go", "/done"], addresses);
+}
+
+#[test]
+fn fail_message_is_passed_only_to_responders_for_fail() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let mut responders: Arc<Responders> = Arc::new(RwLock::new(Vec::new()));
+    for address in &["/n_set", "/fail"] {
+        responders.write().unwrap().push((
+            0,
+            Box::new(RecordingResponder {
+                address: address.to_string(),
+                received: received.clone(),
+            }),
+        ));
+    }
+
+    let fail = message("/fail", vec!["/n_set".into(), "Node 1000 not found".into()]);
+    OscServer::handle_packet(fail, &mut responders).unwrap();
+
+    let received = received.lock().unwrap();
+    assert_eq!(1, received.len());
+    assert_eq!("/fail", received[0].addr);
+}
+
+struct PanickingResponder;
+
+impl OscResponder for PanickingResponder {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        panic!("unexpected message: {:?}", message);
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        AfterCallAction::Reschedule
+    }
+
+    fn get_address(&self) -> String {
+        String::from("/n_go")
+    }
+}
+
+#[test]
+fn handle_packet_removes_panicking_responder() {
+    let mut responders: Arc<Responders> = Arc::new(RwLock::new(Vec::new()));
+    responders
+        .write()
+        .unwrap()
+        .push((0, Box::new(PanickingResponder)));
+
+    let packet = message("/n_go", vec![1000.into()]);
+    let error = OscServer::handle_packet(packet, &mut responders)
+        .err()
+        .unwrap();
+    assert!(matches!(error, ScClientError::Responder(_)));
+    assert!(responders.read().unwrap().is_empty());
+}
+
+/// Replies to each `/sync` of the first connection with `/synced`, which ID is shifted by
+/// `sync_id_shift`.
+fn spawn_sync_server(sync_id_shift: i32) -> String {
+    spawn_tcp_server(1, None, move |packet| match decoder::decode(&packet) {
+        Ok(OscPacket::Message(sync)) => match sync.args.unwrap_or_default().first() {
+            Some(OscType::Int(sync_id)) => {
+                let reply = message("/synced", vec![(sync_id + sync_id_shift).into()]);
+                Some(encoder::encode(&reply).unwrap())
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+#[test]
+fn sync_waits_for_synced_with_same_id() {
+    let osc_server = OscServer::new_tcp(&spawn_sync_server(0), None).unwrap();
+    osc_server.sync().unwrap();
+    osc_server.sync().unwrap();
+}
+
+#[test]
+fn sync_timeout_ignores_synced_with_other_id() {
+    let osc_server = OscServer::new_tcp(&spawn_sync_server(1), None).unwrap();
+    let timeout = Duration::from_millis(100);
+    let error = osc_server.sync_timeout(timeout).err().unwrap();
+    assert!(matches!(error, ScClientError::SyncTimeout(t) if t == timeout));
+}
+
+/// Keeps the sent packets and never receives anything.
+#[derive(Clone)]
+struct RecordingTransport {
+    sent: Arc<Mutex<Vec<OscPacket>>>,
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, packet: &[u8]) -> ScClientResult<usize> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push(decoder::decode(packet).unwrap());
+        Ok(packet.len())
+    }
+
+    fn receive(&self) -> ScClientResult<Vec<u8>> {
+        loop {
+            thread::park();
+        }
+    }
+}
+
+#[test]
+fn bundle_collects_messages_of_current_thread() {
+    let transport = RecordingTransport {
+        sent: Arc::new(Mutex::new(Vec::new())),
+    };
+    let osc_server = Arc::new(OscServer::with_transport(transport.clone()));
+
+    osc_server.start_bundle().unwrap();
+    osc_server.send_message("/s_new", None).unwrap();
+    let other_thread_server = osc_server.clone();
+    thread::spawn(move || other_thread_server.send_message("/status", None).unwrap())
+        .join()
+        .unwrap();
+    osc_server.send_message("/n_set", None).unwrap();
+    osc_server.finish_bundle(OscTime::IMMEDIATELY).unwrap();
+
+    let sent = transport.sent.lock().unwrap();
+    assert_eq!(2, sent.len());
+    assert!(matches!(&sent[0], OscPacket::Message(message) if message.addr == "/status"));
+    match &sent[1] {
+        OscPacket::Bundle(bundle) => assert_eq!(2, bundle.content.len()),
+        packet => panic!("unexpected packet: {:?}", packet),
+    }
+}
+
+/// Replies to `/sync` with `/synced` and receives the packets, which are put into `incoming`.
+struct LoopbackTransport {
+    incoming: mpsc::Sender<Vec<u8>>,
+    received: Mutex<mpsc::Receiver<Vec<u8>>>,
+}
+
+impl LoopbackTransport {
+    fn new() -> Self {
+        let (incoming, received) = mpsc::channel();
+        LoopbackTransport {
+            incoming,
+            received: Mutex::new(received),
+        }
+    }
+}
+
+impl Transport for LoopbackTransport {
+    fn send(&self, packet: &[u8]) -> ScClientResult<usize> {
+        if let Ok(OscPacket::Message(sync)) = decoder::decode(packet) {
+            let reply = message("/synced", sync.args.unwrap());
+            self.incoming
+                .send(encoder::encode(&reply).unwrap())
+                .unwrap();
+        }
+        Ok(packet.len())
+    }
+
+    fn receive(&self) -> ScClientResult<Vec<u8>> {
+        Ok(self.received.lock().unwrap().recv().unwrap())
+    }
+}
+
+#[test]
+fn listener_reports_errors_and_keeps_running() {
+    let transport = LoopbackTransport::new();
+    let incoming = transport.incoming.clone();
+    let osc_server = OscServer::with_transport(transport);
+    let (error_sender, errors) = mpsc::channel();
+    let error_sender = Mutex::new(error_sender);
+    osc_server.set_error_callback(move |e| {
+        let is_decode_error = matches!(e, ScClientError::Decode(_));
+        error_sender.lock().unwrap().send(is_decode_error).unwrap();
+    });
+
+    incoming.send(b"not an OSC packet".to_vec()).unwrap();
+    assert!(errors.recv_timeout(Duration::from_secs(1)).unwrap());
+    osc_server.sync_timeout(Duration::from_secs(1)).unwrap();
+}
+
+#[test]
+fn sync_reports_failure_received_before_synced() {
+    let transport = LoopbackTransport::new();
+    let incoming = transport.incoming.clone();
+    let osc_server = OscServer::with_transport(transport);
+    let fail = message(
+        "/fail",
+        vec![
+            OscType::String("/s_new".to_string()),
+            OscType::String("SynthDef not found".to_string()),
+            OscType::Int(1000),
+        ],
+    );
+    incoming.send(encoder::encode(&fail).unwrap()).unwrap();
+
+    let timeout = Duration::from_secs(1);
+    match osc_server.sync_timeout(timeout) {
+        Err(ScClientError::ServerFailure(failure)) => {
+            assert_eq!(failure.command, "/s_new");
+            assert_eq!(failure.message, "SynthDef not found");
+            assert_eq!(failure.extra_args, vec![OscType::Int(1000)]);
+        }
+        _ => panic!("the failure isn't reported"),
+    }
+    osc_server.sync_timeout(timeout).unwrap();
+}
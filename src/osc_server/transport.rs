@@ -0,0 +1,203 @@
+#[cfg(test)]
+mod tests;
+use crate::ScClientResult;
+use log::warn;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, SocketAddrV4, TcpStream, UdpSocket};
+use std::str::FromStr;
+use std::sync::{Condvar, Mutex};
+
+/// Maximum size of a packet, which the server sends or accepts over TCP.
+pub const MAX_TCP_PACKET_SIZE: usize = 65536;
+
+/// Sends and receives encoded OSC packets.
+pub trait Transport: Send + Sync + 'static {
+    /// Sends the packet to the server.
+    fn send(&self, packet: &[u8]) -> ScClientResult<usize>;
+    /// Blocks until the next packet from the server is received.
+    fn receive(&self) -> ScClientResult<Vec<u8>>;
+}
+
+/// Sends each packet as a single datagram, so a packet can't be bigger than
+/// [`rosc::decoder::MTU`](../rosc/decoder/constant.MTU.html).
+pub struct UdpTransport {
+    socket: UdpSocket,
+    server_address: SocketAddrV4,
+}
+
+impl UdpTransport {
+    //! The addresses are in `ip:port` format.
+    pub fn new(client_address: &str, server_address: &str) -> ScClientResult<Self> {
+        let client_address = SocketAddrV4::from_str(client_address)?;
+        Ok(UdpTransport {
+            socket: UdpSocket::bind(client_address)?,
+            server_address: SocketAddrV4::from_str(server_address)?,
+        })
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send(&self, packet: &[u8]) -> ScClientResult<usize> {
+        Ok(self.socket.send_to(packet, self.server_address)?)
+    }
+
+    fn receive(&self) -> ScClientResult<Vec<u8>> {
+        let mut buf = [0u8; rosc::decoder::MTU];
+        loop {
+            let (size, address) = self.socket.recv_from(&mut buf)?;
+            if address == SocketAddr::from(self.server_address) {
+                return Ok(buf[..size].to_vec());
+            }
+            warn!("Reject packet from unknow host: {}", address);
+        }
+    }
+}
+
+/// Sends each packet prefixed with its size as a big-endian int32, like the server expects
+/// on its TCP port.
+///
+/// The connection is opened on the first sent packet, so the transport can be created before
+/// the server is booted. If the connection is lost, it's reopened on the next sent packet.
+///
+/// A server started with a session password rejects the commands of a connection, which
+/// doesn't start with the password, so it's sent first on each connection.
+pub struct TcpTransport {
+    server_address: SocketAddrV4,
+    session_password: Option<String>,
+    writer: Mutex<Option<TcpStream>>,
+    reader: Mutex<Option<TcpStream>>,
+    connected: Condvar,
+}
+
+impl TcpTransport {
+    //! The address is in `ip:port` format.
+    pub fn new(server_address: &str) -> ScClientResult<Self> {
+        TcpTransport::with_session_password(server_address, None)
+    }
+
+    pub fn with_session_password(
+        server_address: &str,
+        session_password: Option<&str>,
+    ) -> ScClientResult<Self> {
+        Ok(TcpTransport {
+            server_address: SocketAddrV4::from_str(server_address)?,
+            session_password: session_password.map(String::from),
+            writer: Mutex::new(None),
+            reader: Mutex::new(None),
+            connected: Condvar::new(),
+        })
+    }
+
+    fn connect(&self) -> ScClientResult<TcpStream> {
+        let mut stream = TcpStream::connect(self.server_address)?;
+        stream.set_nodelay(true)?;
+        if let Some(session_password) = &self.session_password {
+            // the server compares the raw first packet with its password
+            write_packet(&mut stream, session_password.as_bytes())?;
+        }
+        *self.reader.lock().unwrap() = Some(stream.try_clone()?);
+        self.connected.notify_all();
+        Ok(stream)
+    }
+}
+
+impl Transport for TcpTransport {
+    fn send(&self, packet: &[u8]) -> ScClientResult<usize> {
+        let mut writer = self.writer.lock().unwrap();
+        if writer.is_none() {
+            *writer = Some(self.connect()?);
+        }
+
+        let stream = writer.as_mut().unwrap();
+        if let Err(e) = write_packet(stream, packet) {
+            // makes the receiving side drop the connection too
+            let _ = stream.shutdown(Shutdown::Both);
+            *writer = None;
+            return Err(e.into());
+        }
+        Ok(packet.len())
+    }
+
+    fn receive(&self) -> ScClientResult<Vec<u8>> {
+        let (result, local_address) = {
+            let mut reader = self.reader.lock().unwrap();
+            while reader.is_none() {
+                reader = self.connected.wait(reader).unwrap();
+            }
+            let stream = reader.as_mut().unwrap();
+            let local_address = stream.local_addr().ok();
+            let result = read_packet(stream);
+            if result.is_err() {
+                *reader = None;
+            }
+            (result, local_address)
+        };
+
+        // the writer is locked after the reader is released, because `send` locks them in the
+        // opposite order on connecting. It could be reconnected meanwhile, so it's dropped only
+        // if it's the same connection.
+        if result.is_err() {
+            let mut writer = self.writer.lock().unwrap();
+            if writer.as_ref().and_then(|w| w.local_addr().ok()) == local_address {
+                *writer = None;
+            }
+        }
+        Ok(result?)
+    }
+}
+
+fn write_packet(stream: &mut TcpStream, packet: &[u8]) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(packet.len() + 4);
+    buf.extend_from_slice(&(packet.len() as i32).to_be_bytes());
+    buf.extend_from_slice(packet);
+    stream.write_all(&buf)
+}
+
+fn read_packet(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut size = [0u8; 4];
+    stream.read_exact(&mut size)?;
+    let size = i32::from_be_bytes(size);
+    // a corrupt size would make it allocate up to 2 GiB
+    if !(0..=MAX_TCP_PACKET_SIZE as i32).contains(&size) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid size of TCP packet: {}", size),
+        ));
+    }
+    let mut buf = vec![0u8; size as usize];
+    stream.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Starts a TCP stand-in of the server for the tests and returns its address. It accepts
+/// `num_of_connections` connections one by one, passes each received packet to `on_packet`
+/// and sends back the reply, if there's one. A connection is closed by the client or after
+/// `max_packets_per_connection` packets.
+#[cfg(test)]
+pub(crate) fn spawn_tcp_server<F>(
+    num_of_connections: usize,
+    max_packets_per_connection: Option<usize>,
+    mut on_packet: F,
+) -> String
+where
+    F: FnMut(Vec<u8>) -> Option<Vec<u8>> + Send + 'static,
+{
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    std::thread::spawn(move || {
+        for _ in 0..num_of_connections {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut num_of_packets = 0;
+            while let Ok(packet) = read_packet(&mut stream) {
+                if let Some(reply) = on_packet(packet) {
+                    write_packet(&mut stream, &reply).unwrap();
+                }
+                num_of_packets += 1;
+                if Some(num_of_packets) == max_packets_per_connection {
+                    break;
+                }
+            }
+        }
+    });
+    address
+}
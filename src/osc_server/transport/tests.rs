@@ -0,0 +1,70 @@
+use super::*;
+use crate::ScClientError;
+
+/// Accepts `num_of_connections` connections one by one. Sends back the first packet of each
+/// connection and closes it.
+fn spawn_echo_server(num_of_connections: usize) -> String {
+    spawn_tcp_server(num_of_connections, Some(1), Some)
+}
+
+#[test]
+fn tcp_transport_sends_length_prefixed_packets() {
+    let transport = TcpTransport::new(&spawn_echo_server(1)).unwrap();
+    assert_eq!(8, transport.send(b"/status\0").unwrap());
+    assert_eq!(b"/status\0".to_vec(), transport.receive().unwrap());
+}
+
+#[test]
+fn tcp_transport_reconnects_after_connection_is_closed() {
+    let transport = TcpTransport::new(&spawn_echo_server(2)).unwrap();
+    transport.send(b"/sync\0\0\0").unwrap();
+    assert_eq!(b"/sync\0\0\0".to_vec(), transport.receive().unwrap());
+    assert!(transport.receive().is_err());
+
+    transport.send(b"/quit\0\0\0").unwrap();
+    assert_eq!(b"/quit\0\0\0".to_vec(), transport.receive().unwrap());
+}
+
+#[test]
+fn tcp_transport_sends_session_password_first_on_each_connection() {
+    let (sender, packets) = std::sync::mpsc::channel();
+    let address = spawn_tcp_server(2, Some(2), move |packet| {
+        sender.send(packet).unwrap();
+        None
+    });
+    let transport = TcpTransport::with_session_password(&address, Some("secret")).unwrap();
+    transport.send(b"/sync\0\0\0").unwrap();
+    assert!(transport.receive().is_err());
+
+    transport.send(b"/quit\0\0\0").unwrap();
+    let packets: Vec<Vec<u8>> = packets.iter().take(4).collect();
+    assert_eq!(
+        vec![
+            b"secret".to_vec(),
+            b"/sync\0\0\0".to_vec(),
+            b"secret".to_vec(),
+            b"/quit\0\0\0".to_vec(),
+        ],
+        packets
+    );
+}
+
+#[test]
+fn tcp_transport_rejects_oversized_packet() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let size = MAX_TCP_PACKET_SIZE as i32 + 1;
+        stream.write_all(&size.to_be_bytes()).unwrap();
+        // keeps the connection open, until the client closes it
+        let _ = stream.read(&mut [0u8; 1]);
+    });
+    let transport = TcpTransport::new(&address).unwrap();
+    transport.send(b"/status\0").unwrap();
+
+    match transport.receive() {
+        Err(ScClientError::Io(e)) => assert_eq!(io::ErrorKind::InvalidData, e.kind()),
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
@@ -1,93 +1,797 @@
 mod notify_responder;
 mod options;
+mod query_tree_responder;
 mod quit_responder;
 mod sc_server_process;
+mod server_output;
 mod status_responder;
+mod supervisor;
+#[cfg(test)]
+mod tests;
 mod version_responder;
-use self::notify_responder::NotifyResponder;
+use self::notify_responder::{NotifyReply, NotifyResponder};
 pub use self::options::Options;
+pub(crate) use self::query_tree_responder::QueryTreeResponder;
 use self::quit_responder::QuitResponder;
 use self::sc_server_process::ScServerProcess;
-use self::status_responder::StatusResponder;
-use self::version_responder::VersionResponder;
-use crate::{types::OscType, OscServer, ScClientResult};
-use failure::Fail;
-use std::cell::RefCell;
+pub use self::server_output::{
+    OutputLine, OutputStream, ServerOutput, DEFAULT_OUTPUT_HISTORY_SIZE,
+};
+pub(crate) use self::status_responder::StatusResponder;
+use self::supervisor::Supervisor;
+pub use self::supervisor::{
+    ServerEvent, DEFAULT_MAX_REBOOT_ATTEMPTS, STABLE_RUN_TIME, SUPERVISION_INTERVAL,
+};
+pub(crate) use self::version_responder::VersionResponder;
+use crate::osc_server::PendingRequest;
+use crate::{
+    types::NodeValue, types::OscTime, types::OscType, BlockAllocator, NodeEvent, NodeIdAllocator,
+    NodeWatcher, OscResponder, OscServer, ScClientError, ScClientResult, ServerFailure,
+    DEFAULT_SYNC_TIMEOUT,
+};
+use log::{error, warn};
+use std::process::ExitStatus;
+use std::sync::{mpsc, Arc, Mutex, PoisonError, RwLock, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
 
+/// How long [`Server::boot`](struct.Server.html#method.boot) waits for the server to get ready
+/// by default.
+pub const DEFAULT_BOOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Handle of the server. Clones of the handle share the same connection and state, so they
+/// can be passed to other threads.
+#[derive(Clone)]
 pub struct Server {
-    pub options: RefCell<Options>,
-    pub osc_server: RefCell<OscServer>,
-    sc_server_process: RefCell<Option<ScServerProcess>>,
+    pub options: Arc<RwLock<Options>>,
+    pub osc_server: Arc<OscServer>,
+    node_watcher: NodeWatcher,
+    output: ServerOutput,
+    supervisor: Supervisor,
+    state: Arc<ServerState>,
+}
+
+/// Handle of the server, which doesn't keep the server process alive.
+struct WeakServer {
+    options: Arc<RwLock<Options>>,
+    osc_server: Arc<OscServer>,
+    node_watcher: NodeWatcher,
+    output: ServerOutput,
+    supervisor: Supervisor,
+    state: Weak<ServerState>,
+}
+
+impl WeakServer {
+    fn upgrade(&self) -> Option<Server> {
+        Some(Server {
+            options: self.options.clone(),
+            osc_server: self.osc_server.clone(),
+            node_watcher: self.node_watcher.clone(),
+            output: self.output.clone(),
+            supervisor: self.supervisor.clone(),
+            state: self.state.upgrade()?,
+        })
+    }
+}
+
+/// State of the connection to the server, which is started by another program.
+#[derive(Clone, Copy, PartialEq)]
+enum Connection {
+    Disconnected,
+    /// the server is checked with `/status`.
+    Connecting,
+    Connected,
+}
+
+struct ServerState {
+    sc_server_process: Mutex<Option<ScServerProcess>>,
+    audio_bus_allocator: Mutex<BlockAllocator>,
+    control_bus_allocator: Mutex<BlockAllocator>,
+    buffer_allocator: Mutex<BlockAllocator>,
+    node_id_allocator: Mutex<NodeIdAllocator>,
+    client_id: Mutex<Option<i32>>,
+    max_logins: Mutex<Option<i32>>,
+    latency: Mutex<Duration>,
+    sync_timeout: Mutex<Duration>,
+    boot_timeout: Mutex<Duration>,
+    connection: Mutex<Connection>,
+    is_keeping_external_running: Mutex<bool>,
+    is_shutting_down: Mutex<bool>,
+    max_reboot_attempts: Mutex<usize>,
+    num_of_reboots: Mutex<usize>,
 }
 
 impl Server {
-    pub fn new(options: Options) -> Self {
+    /// Creates the handle of the server. Fails, if the addresses of the options are invalid or
+    /// the client's UDP port can't be bound. The server isn't booted or connected yet.
+    pub fn new(options: Options) -> ScClientResult<Self> {
+        let osc_server = Server::init_osc_server(&options)?;
+        let node_watcher = NodeWatcher::new();
+        for responder in node_watcher.responders() {
+            osc_server.add_responder(responder)?;
+        }
+
+        let state = ServerState {
+            sc_server_process: Mutex::new(None),
+            audio_bus_allocator: Mutex::new(Server::init_audio_bus_allocator(&options)),
+            control_bus_allocator: Mutex::new(Server::init_control_bus_allocator(&options)),
+            buffer_allocator: Mutex::new(Server::init_buffer_allocator(&options)),
+            node_id_allocator: Mutex::new(NodeIdAllocator::new(0)),
+            client_id: Mutex::new(None),
+            max_logins: Mutex::new(None),
+            latency: Mutex::new(Duration::from_millis(200)),
+            sync_timeout: Mutex::new(DEFAULT_SYNC_TIMEOUT),
+            boot_timeout: Mutex::new(DEFAULT_BOOT_TIMEOUT),
+            connection: Mutex::new(Connection::Disconnected),
+            is_keeping_external_running: Mutex::new(false),
+            is_shutting_down: Mutex::new(false),
+            max_reboot_attempts: Mutex::new(DEFAULT_MAX_REBOOT_ATTEMPTS),
+            num_of_reboots: Mutex::new(0),
+        };
+        Ok(Server {
+            options: Arc::new(RwLock::new(options)),
+            osc_server: Arc::new(osc_server),
+            node_watcher,
+            output: ServerOutput::new(),
+            supervisor: Supervisor::default(),
+            state: Arc::new(state),
+        })
+    }
+
+    /// Uses TCP only if the UDP port isn't specified.
+    fn init_osc_server(options: &Options) -> ScClientResult<OscServer> {
+        if options.udp_port_number == 0 {
+            let server_address = format!("{}:{}", options.address, options.tcp_port_number);
+            return OscServer::new_tcp(&server_address, options.session_password.as_deref());
+        }
         let server_address = format!("{}:{}", options.address, options.udp_port_number);
         let client_address = format!("{}:{}", options.client_address, options.client_port);
-        let osc_server = OscServer::new(&client_address, &server_address);
+        OscServer::new(&client_address, &server_address)
+    }
 
-        Server {
-            options: RefCell::new(options),
-            sc_server_process: RefCell::new(None),
-            osc_server: RefCell::new(osc_server),
-        }
+    fn init_audio_bus_allocator(options: &Options) -> BlockAllocator {
+        // the first buses are used by the hardware inputs and outputs
+        let num_of_hardware_channels =
+            options.num_input_bus_channels as i32 + options.num_output_bus_channels as i32;
+        BlockAllocator::new(
+            num_of_hardware_channels,
+            options.num_audio_bus_channels as i32 - num_of_hardware_channels,
+        )
     }
 
+    fn init_control_bus_allocator(options: &Options) -> BlockAllocator {
+        BlockAllocator::new(0, options.num_control_bus_channels as i32)
+    }
+
+    fn init_buffer_allocator(options: &Options) -> BlockAllocator {
+        BlockAllocator::new(0, options.num_buffers as i32)
+    }
+
+    /// Starts the server process and blocks until it's ready to receive commands. Fails with
+    /// [`ScClientError::BootTimeout`](enum.ScClientError.html), if it isn't ready in
+    /// [the boot timeout](#method.set_boot_timeout), with `ProcessExited`, if it exits before,
+    /// or with `BootFailed`, if it prints a known failure, like a busy port. The errors
+    /// contain the output of the process.
+    ///
+    /// After the server is ready, the [boot actions](#method.add_boot_action) are performed
+    /// and the process is watched for exit (see [`subscribe_events`](#method.subscribe_events)).
+    /// If an action fails, the process is killed.
     pub fn boot(&self) -> ScClientResult<&Self> {
-        let mut proc = self.sc_server_process.borrow_mut();
+        *self.state.num_of_reboots.lock().unwrap() = 0;
+        self.boot_process()
+    }
 
-        if proc.is_some() {
-            return Err(ServerError::AlreadyRunning.into());
+    fn boot_process(&self) -> ScClientResult<&Self> {
+        let process_id = {
+            let mut proc = self.state.sc_server_process.lock().unwrap();
+
+            if proc.is_some()
+                || *self.state.is_shutting_down.lock().unwrap()
+                || *self.state.connection.lock().unwrap() != Connection::Disconnected
+            {
+                return Err(ScClientError::AlreadyRunning);
+            }
+
+            self.node_watcher.clear();
+            self.reset_allocators();
+            let process = ScServerProcess::new(
+                &self.options.read().unwrap(),
+                self.get_boot_timeout(),
+                &self.output,
+            )?;
+            let process_id = process.id();
+            *proc = Some(process);
+            process_id
+        };
+
+        self.supervise(process_id);
+        if let Err(e) = self.supervisor.on_boot(self) {
+            // the supervision stops, when the process is gone
+            self.kill_process(process_id);
+            return Err(e);
+        }
+        Ok(self)
+    }
+
+    fn kill_process(&self, process_id: u32) {
+        let mut proc = self.state.sc_server_process.lock().unwrap();
+        if proc.as_ref().map(ScServerProcess::id) != Some(process_id) {
+            return;
+        }
+        let mut process = proc.take().unwrap();
+        if let Err(e) = process.kill_child().and_then(|_| process.wait_for_finish()) {
+            error!("Error kill SuperCollider server: {}", e);
         }
+    }
 
-        *proc = Some(ScServerProcess::new(&self.options.borrow())?);
+    /// Connects to the server, which is already running, for example, the one started by
+    /// another program or running on another host, instead of booting it. The server is
+    /// checked with `/status`, so it fails with
+    /// [`ScClientError::ReplyTimeout`](enum.ScClientError.html), if the server doesn't reply in
+    /// [the sync timeout](#method.set_sync_timeout).
+    ///
+    /// The connected server isn't supervised, and the [boot actions](#method.add_boot_action)
+    /// aren't performed. See [`shutdown`](#method.shutdown) for how it's shut down.
+    pub fn connect(&self) -> ScClientResult<&Self> {
+        {
+            let proc = self.state.sc_server_process.lock().unwrap();
+            let mut connection = self.state.connection.lock().unwrap();
+            if proc.is_some() || *connection != Connection::Disconnected {
+                return Err(ScClientError::AlreadyRunning);
+            }
+            // the locks aren't held, while waiting for the status, so the connection is
+            // reserved to keep the other boots and connects out
+            *connection = Connection::Connecting;
+        }
 
+        self.node_watcher.clear();
+        self.reset_allocators();
+        let result = self.status();
+        *self.state.connection.lock().unwrap() = match result {
+            Ok(_) => Connection::Connected,
+            Err(_) => Connection::Disconnected,
+        };
+        result?;
         Ok(self)
     }
 
+    /// Watches the server process with `process_id` in the background, until it exits, or the
+    /// server is shut down, rebooted or dropped.
+    fn supervise(&self, process_id: u32) {
+        let weak_server = self.downgrade();
+        let start_time = Instant::now();
+        thread::spawn(move || loop {
+            thread::sleep(SUPERVISION_INTERVAL);
+            let server = match weak_server.upgrade() {
+                Some(server) => server,
+                None => return,
+            };
+
+            let result = {
+                let mut proc = server.state.sc_server_process.lock().unwrap();
+                let result = match proc.as_mut() {
+                    Some(process) if process.id() == process_id => process.try_wait(),
+                    _ => return,
+                };
+                if let Ok(Some(_)) = result {
+                    *proc = None;
+                }
+                result
+            };
+            match result {
+                Ok(None) => continue,
+                Ok(Some(status)) => server.on_process_exit(status, start_time.elapsed()),
+                Err(e) => server.osc_server.report_error(&e),
+            }
+            return;
+        });
+    }
+
+    fn on_process_exit(&self, status: ExitStatus, run_time: Duration) {
+        warn!("SuperCollider server has exited with {}", status);
+        self.supervisor.emit(&ServerEvent::Exited(status));
+        if !self.is_auto_reboot() || *self.state.is_shutting_down.lock().unwrap() {
+            return;
+        }
+
+        {
+            let mut num_of_reboots = self.state.num_of_reboots.lock().unwrap();
+            if run_time >= STABLE_RUN_TIME {
+                *num_of_reboots = 0;
+            }
+            let max_reboot_attempts = self.get_max_reboot_attempts();
+            if *num_of_reboots >= max_reboot_attempts {
+                error!(
+                    "SuperCollider server isn't rebooted, it has exited {} times in a row",
+                    max_reboot_attempts + 1
+                );
+                return;
+            }
+            *num_of_reboots += 1;
+        }
+        match self.boot_process() {
+            Ok(_) => self.supervisor.emit(&ServerEvent::Rebooted),
+            Err(e) => {
+                error!("Error reboot SuperCollider server");
+                self.osc_server.report_error(&e);
+            }
+        }
+    }
+
+    fn downgrade(&self) -> WeakServer {
+        WeakServer {
+            options: self.options.clone(),
+            osc_server: self.osc_server.clone(),
+            node_watcher: self.node_watcher.clone(),
+            output: self.output.clone(),
+            supervisor: self.supervisor.clone(),
+            state: Arc::downgrade(&self.state),
+        }
+    }
+
+    /// Calls `callback` on each [`ServerEvent`](enum.ServerEvent.html), for example, when the
+    /// server process has exited by itself. It's called from a background thread.
+    pub fn subscribe_events<F>(&self, callback: F) -> usize
+    where
+        F: Fn(&ServerEvent) + Send + Sync + 'static,
+    {
+        self.supervisor.subscribe(callback)
+    }
+
+    pub fn unsubscribe_events(&self, subscription_id: usize) {
+        self.supervisor.unsubscribe(subscription_id);
+    }
+
+    /// Sets whether the server is booted again with the same options, when its process has
+    /// exited by itself. The reboot errors are passed to
+    /// [the error callback](#method.set_error_callback). It's off by default.
+    ///
+    /// The server is rebooted no more than [the maximum attempts](#method.set_max_reboot_attempts)
+    /// in a row, so a server, which keeps crashing, isn't restarted forever. The attempts are
+    /// counted anew after [`boot`](#method.boot), or if the server has run for
+    /// [`STABLE_RUN_TIME`](constant.STABLE_RUN_TIME.html) before exiting.
+    pub fn set_auto_reboot(&self, is_auto_reboot: bool) -> &Self {
+        self.supervisor.set_auto_reboot(is_auto_reboot);
+        self
+    }
+
+    pub fn is_auto_reboot(&self) -> bool {
+        self.supervisor.is_auto_reboot()
+    }
+
+    /// Sets how many times in a row the server is rebooted automatically. It's
+    /// [`DEFAULT_MAX_REBOOT_ATTEMPTS`](constant.DEFAULT_MAX_REBOOT_ATTEMPTS.html) by default.
+    pub fn set_max_reboot_attempts(&self, max_reboot_attempts: usize) -> &Self {
+        *self.state.max_reboot_attempts.lock().unwrap() = max_reboot_attempts;
+        self
+    }
+
+    pub fn get_max_reboot_attempts(&self) -> usize {
+        *self.state.max_reboot_attempts.lock().unwrap()
+    }
+
+    /// Adds the action, which is performed after each boot, including the automatic reboots,
+    /// to restore the state of the server: load the synth definitions, create the groups,
+    /// turn the notifications on and so on. The actions are performed in the order they were
+    /// added, and [`boot`](#method.boot) kills the server and fails with the first error.
+    ///
+    /// ```no_run
+    /// # use sc_client::{Options, ScClientResult, Server, SynthDefinition};
+    /// # fn main() -> ScClientResult<()> {
+    /// let server = Server::new(Options::default())?;
+    /// server.add_boot_action(|server| {
+    ///     SynthDefinition::load_directory(server, "synthdefs")?;
+    ///     server.sync()?;
+    ///     Ok(())
+    /// });
+    /// server.set_auto_reboot(true).boot()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_boot_action<F>(&self, action: F) -> usize
+    where
+        F: Fn(&Server) -> ScClientResult<()> + Send + Sync + 'static,
+    {
+        self.supervisor.add_boot_action(action)
+    }
+
+    pub fn remove_boot_action(&self, action_id: usize) {
+        self.supervisor.remove_boot_action(action_id);
+    }
+
     pub fn reboot(&self) -> ScClientResult<&Self> {
         self.shutdown()?;
         self.boot()?;
         Ok(self)
     }
 
+    /// Quits the booted server and waits for its process to finish. The
+    /// [connected](#method.connect) server is quit too, unless it's
+    /// [kept running](#method.set_keeping_external_running).
     pub fn shutdown(&self) -> ScClientResult<&Self> {
-        let mut proc = self.sc_server_process.borrow_mut();
-        let mut osc_server = self.osc_server.borrow_mut();
-        if proc.is_some() {
-            let quit_responder = QuitResponder {};
-            osc_server.add_responder(quit_responder)?;
+        // the process is taken out, before it's quit, so the supervision doesn't reboot it
+        let process = {
+            let mut proc = self.state.sc_server_process.lock().unwrap();
+            if proc.is_some() {
+                *self.state.is_shutting_down.lock().unwrap() = true;
+            }
+            proc.take()
+        };
+        if let Some(process) = process {
+            let result = self.quit_process(process);
+            *self.state.is_shutting_down.lock().unwrap() = false;
+            result?;
+            return Ok(self);
+        }
 
-            osc_server.send_message("/quit", None)?;
-            osc_server.sync()?;
+        let mut connection = self.state.connection.lock().unwrap();
+        if *connection == Connection::Connected {
+            if !self.is_keeping_external_running() {
+                self.quit()?;
+            }
+            *connection = Connection::Disconnected;
+        }
 
-            proc.as_mut().unwrap().wait_for_finish()?;
+        Ok(self)
+    }
 
-            *proc = None;
+    /// Quits the server of the process, which isn't supervised any more, so it's killed, if it
+    /// can't be quit.
+    fn quit_process(&self, mut process: ScServerProcess) -> ScClientResult<()> {
+        if let Err(e) = self.quit() {
+            if let Err(e) = process.kill_child().and_then(|_| process.wait_for_finish()) {
+                error!("Error kill SuperCollider server: {}", e);
+            }
+            return Err(e);
         }
+        process.wait_for_finish()?;
+        Ok(())
+    }
 
-        Ok(self)
+    fn quit(&self) -> ScClientResult<()> {
+        let osc_server = &self.osc_server;
+        let quit_responder = QuitResponder {};
+        osc_server.add_responder(quit_responder)?;
+
+        osc_server.send_message("/quit", None)?;
+        // a failure of an earlier command shouldn't prevent the shutdown
+        match osc_server.sync_timeout(self.get_sync_timeout()) {
+            Ok(_) | Err(ScClientError::ServerFailure(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Sets whether [`shutdown`](#method.shutdown) leaves the [connected](#method.connect)
+    /// server running and only disconnects from it. It's off by default.
+    pub fn set_keeping_external_running(&self, is_keeping: bool) -> &Self {
+        *self.state.is_keeping_external_running.lock().unwrap() = is_keeping;
+        self
+    }
+
+    pub fn is_keeping_external_running(&self) -> bool {
+        *self.state.is_keeping_external_running.lock().unwrap()
     }
 
     pub fn set_options_and_reboot(&self, opts: Options) -> ScClientResult<&Self> {
-        self.options.replace(opts);
+        *self.state.audio_bus_allocator.lock().unwrap() = Server::init_audio_bus_allocator(&opts);
+        *self.state.control_bus_allocator.lock().unwrap() =
+            Server::init_control_bus_allocator(&opts);
+        *self.state.buffer_allocator.lock().unwrap() = Server::init_buffer_allocator(&opts);
+        *self.options.write().unwrap() = opts;
         self.reboot()
     }
 
+    fn reset_allocators(&self) {
+        self.state.audio_bus_allocator.lock().unwrap().reset();
+        self.state.control_bus_allocator.lock().unwrap().reset();
+        self.state.buffer_allocator.lock().unwrap().reset();
+        let mut node_id_allocator = self.state.node_id_allocator.lock().unwrap();
+        let client_id = node_id_allocator.get_client_id();
+        node_id_allocator.reset(client_id);
+    }
+
+    /// Returns a new node ID in the range of this client.
+    pub fn next_node_id(&self) -> i32 {
+        self.state.node_id_allocator.lock().unwrap().alloc()
+    }
+
+    /// Returns the node ID for reuse. [`Node::free`](trait.Node.html#method.free) returns it,
+    /// after the node has ended.
+    pub fn free_node_id(&self, id: i32) -> &Self {
+        self.state.node_id_allocator.lock().unwrap().free(id);
+        self
+    }
+
+    /// Returns the node ID for reuse, when the server notifies, that the node has ended, so a
+    /// new node doesn't get the ID of the node, which is still running. Returns the ID of the
+    /// subscription to the node's events.
+    pub(crate) fn free_node_id_on_end(&self, id: i32) -> usize {
+        // the state isn't kept alive, if the node never ends
+        let state = Arc::downgrade(&self.state);
+        self.node_watcher.subscribe(id, move |event| {
+            if let (NodeEvent::End(_), Some(state)) = (event, state.upgrade()) {
+                state.node_id_allocator.lock().unwrap().free(id);
+            }
+        })
+    }
+
+    /// Allocates `num_of_channels` sequential audio buses, which don't overlap the hardware
+    /// input and output buses, and returns the index of the first one.
+    /// > all the allocations are reset on boot
+    pub fn allocate_audio_bus(&self, num_of_channels: i32) -> ScClientResult<i32> {
+        self.state
+            .audio_bus_allocator
+            .lock()
+            .unwrap()
+            .alloc(num_of_channels)
+    }
+
+    /// Frees audio buses, which were allocated starting at `index`.
+    pub fn free_audio_bus(&self, index: i32) -> ScClientResult<&Self> {
+        self.state.audio_bus_allocator.lock().unwrap().free(index)?;
+        Ok(self)
+    }
+
+    /// Allocates `num_of_channels` sequential control buses and returns the index of the first
+    /// one.
+    /// > all the allocations are reset on boot
+    pub fn allocate_control_bus(&self, num_of_channels: i32) -> ScClientResult<i32> {
+        self.state
+            .control_bus_allocator
+            .lock()
+            .unwrap()
+            .alloc(num_of_channels)
+    }
+
+    /// Frees control buses, which were allocated starting at `index`.
+    pub fn free_control_bus(&self, index: i32) -> ScClientResult<&Self> {
+        self.state
+            .control_bus_allocator
+            .lock()
+            .unwrap()
+            .free(index)?;
+        Ok(self)
+    }
+
+    /// Allocates `num_of_buffers` sequential buffer numbers and returns the first one.
+    /// > all the allocations are reset on boot
+    pub fn allocate_buffer_numbers(&self, num_of_buffers: i32) -> ScClientResult<i32> {
+        self.state
+            .buffer_allocator
+            .lock()
+            .unwrap()
+            .alloc(num_of_buffers)
+    }
+
+    /// Frees buffer numbers, which were allocated starting at `number`.
+    pub fn free_buffer_numbers(&self, number: i32) -> ScClientResult<&Self> {
+        self.state.buffer_allocator.lock().unwrap().free(number)?;
+        Ok(self)
+    }
+
+    /// Waits until the server has processed all the previous commands, but not longer than
+    /// [the sync timeout](#method.set_sync_timeout). The commands without replies, like
+    /// `/s_new` or `/d_load`, report their `/fail` here as
+    /// [`ScClientError::ServerFailure`](enum.ScClientError.html).
     pub fn sync(&self) -> ScClientResult<&Self> {
-        let mut osc_server = self.osc_server.borrow_mut();
-        osc_server.sync()?;
+        self.osc_server.sync_timeout(self.get_sync_timeout())?;
+        Ok(self)
+    }
+
+    /// Sets how long [`sync`](#method.sync) and the blocking requests, like
+    /// [`status`](#method.status), wait for the reply. Defaults to
+    /// [`DEFAULT_SYNC_TIMEOUT`](constant.DEFAULT_SYNC_TIMEOUT.html).
+    pub fn set_sync_timeout(&self, timeout: Duration) -> &Self {
+        *self.state.sync_timeout.lock().unwrap() = timeout;
+        self
+    }
+
+    pub fn get_sync_timeout(&self) -> Duration {
+        *self.state.sync_timeout.lock().unwrap()
+    }
+
+    /// Sets how long [`boot`](#method.boot) waits for the server to get ready. The default is
+    /// [`DEFAULT_BOOT_TIMEOUT`](constant.DEFAULT_BOOT_TIMEOUT.html).
+    pub fn set_boot_timeout(&self, timeout: Duration) -> &Self {
+        *self.state.boot_timeout.lock().unwrap() = timeout;
+        self
+    }
+
+    pub fn get_boot_timeout(&self) -> Duration {
+        *self.state.boot_timeout.lock().unwrap()
+    }
+
+    /// Adds the responder, which `make_responder` makes with the callback for the reply, sends
+    /// `command`, syncs right after it and blocks until the sync, but not longer than
+    /// [the sync timeout](#method.set_sync_timeout). Returns the first reply. See
+    /// [`Reply::resolve`](enum.Reply.html#method.resolve) for the errors.
+    pub(crate) fn wait_for_reply<T, R, M>(
+        &self,
+        command: &str,
+        arguments: Option<Vec<OscType>>,
+        make_responder: M,
+    ) -> ScClientResult<T>
+    where
+        T: Send + 'static,
+        R: OscResponder,
+        M: FnOnce(Box<dyn Fn(T) + Send + Sync>) -> R,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let send = |osc_server: &OscServer| osc_server.send_message(command, arguments).map(|_| ());
+        // the responder is removed and the sync is cancelled on any return
+        let _request = self.send_request(command, make_responder, send, move |reply| {
+            // the receiver is gone, if the request has timed out already
+            let _ = sender.send(reply);
+        })?;
+
+        let timeout = self.get_sync_timeout();
+        let deadline = Instant::now() + timeout;
+        let mut value = None;
+        loop {
+            match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(reply) => {
+                    if let Some(result) = reply.resolve(&mut value) {
+                        return result;
+                    }
+                }
+                // the reply is enough, if the server hasn't replied to the sync only
+                Err(_) => return value.ok_or(ScClientError::ReplyTimeout(timeout)),
+            }
+        }
+    }
+
+    /// Adds the responder, which `make_responder` makes, calls `send` to send the messages of
+    /// the request and syncs right after them. Calls `on_reply` with each reply and then with
+    /// the failure of `command` before the sync, if any. The failures of `command`, which the
+    /// other threads send meanwhile, are reported by their syncs.
+    ///
+    /// The responder is removed and the sync is cancelled, when the returned request is dropped.
+    pub(crate) fn send_request<T, R, M, S, F>(
+        &self,
+        command: &str,
+        make_responder: M,
+        send: S,
+        on_reply: F,
+    ) -> ScClientResult<PendingRequest>
+    where
+        T: Send + 'static,
+        R: OscResponder,
+        M: FnOnce(Box<dyn Fn(T) + Send + Sync>) -> R,
+        S: FnOnce(&OscServer) -> ScClientResult<()>,
+        F: Fn(Reply<T>) + Send + Sync + 'static,
+    {
+        let on_reply = Arc::new(on_reply);
+        let on_value = on_reply.clone();
+        let mut request = PendingRequest::new(self.osc_server.clone());
+        request.add_responder(make_responder(Box::new(move |value| {
+            on_value(Reply::Value(value))
+        })))?;
+        let failed_command = command.to_string();
+        request.send_with_sync(send, move |failures| {
+            let failure = failures.into_iter().find(|f| f.command == failed_command);
+            on_reply(Reply::Synced(failure));
+        })?;
+        Ok(request)
+    }
+
+    /// Sets the callback for the errors, which happen in the background, for example, when
+    /// the server sends a malformed packet or a responder fails. See
+    /// [`OscServer::set_error_callback`](struct.OscServer.html#method.set_error_callback).
+    pub fn set_error_callback<F>(&self, callback: F) -> &Self
+    where
+        F: Fn(&ScClientError) + Send + Sync + 'static,
+    {
+        self.osc_server.set_error_callback(callback);
+        self
+    }
+
+    /// Returns [`NodeWatcher`](struct.NodeWatcher.html), which tracks the nodes on the server.
+    /// It's fed only if the notifications are on (see
+    /// [`set_receive_notifications`](#method.set_receive_notifications)).
+    pub fn node_watcher(&self) -> &NodeWatcher {
+        &self.node_watcher
+    }
+
+    /// Returns [`ServerOutput`](struct.ServerOutput.html), which collects the output of the
+    /// server process. The recent lines are kept across reboots.
+    pub fn output(&self) -> &ServerOutput {
+        &self.output
+    }
+
+    /// Sets the time, which is added to the current time for the bundles made with
+    /// [`make_bundle`](#method.make_bundle). It should be large enough to compensate the network
+    /// and scheduling jitter. Defaults to 200ms.
+    pub fn set_latency(&self, latency: Duration) -> &Self {
+        *self.state.latency.lock().unwrap() = latency;
+        self
+    }
+
+    pub fn get_latency(&self) -> Duration {
+        *self.state.latency.lock().unwrap()
+    }
+
+    /// Collects all the commands, which are sent inside `make_commands`, into a single bundle.
+    /// The server executes them atomically at `time` or, if `time` is `None`, at the current
+    /// time plus [latency](#method.set_latency).
+    ///
+    /// ```no_run
+    /// # use sc_client::{AddAction, Node, Options, ScClientResult, Server, Synth};
+    /// # fn main() -> ScClientResult<()> {
+    /// # let server = Server::new(Options::default())?;
+    /// server.make_bundle(None, |server| {
+    ///     let synth = Synth::new(server, "default", &AddAction::Head, 0, &vec![])?;
+    ///     synth.set(&[("freq".into(), 220.0f32.into()).into()])?;
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn make_bundle<F>(&self, time: Option<OscTime>, make_commands: F) -> ScClientResult<&Self>
+    where
+        F: FnOnce(&Self) -> ScClientResult<()>,
+    {
+        self.osc_server.start_bundle()?;
+        let _bundle = BundleGuard {
+            osc_server: &self.osc_server,
+        };
+        make_commands(self)?;
+
+        let time = time.unwrap_or_else(|| OscTime::from_now(self.get_latency()));
+        self.osc_server.finish_bundle(time)?;
         Ok(self)
     }
 
+    /// Register (or unregister) to receive notifications from the server. Waits for the reply,
+    /// but not longer than [the sync timeout](#method.set_sync_timeout), and stores the client
+    /// ID and max logins, which the server assigned to this client. Node IDs are allocated in
+    /// the range of the assigned client ID.
     pub fn set_receive_notifications(&self, is_receiving: bool) -> ScClientResult<&Self> {
-        let notify_responder = NotifyResponder::new(is_receiving);
-        let osc_server = self.osc_server.borrow();
-        osc_server.add_responder(notify_responder)?;
-        osc_server.send_message("/notify", Some(vec![(is_receiving as i32).into()]))?;
+        self.set_receive_notifications_with_client_id(is_receiving, None)
+    }
+
+    /// The same as [`set_receive_notifications`](#method.set_receive_notifications), but
+    /// requests the specific client ID. Used to get the same ID after reconnecting to the
+    /// server, for example, after a crash.
+    pub fn set_receive_notifications_with_client_id(
+        &self,
+        is_receiving: bool,
+        client_id: Option<i32>,
+    ) -> ScClientResult<&Self> {
+        let mut send_args = vec![(is_receiving as i32).into()];
+        if let Some(id) = client_id {
+            send_args.push(id.into());
+        }
+        let reply: NotifyReply = self.wait_for_reply("/notify", Some(send_args), |on_reply| {
+            NotifyResponder::new(is_receiving, on_reply)
+        })?;
+        if is_receiving {
+            self.set_client_id(reply.client_id, reply.max_logins);
+        }
         Ok(self)
     }
 
+    fn set_client_id(&self, client_id: Option<i32>, max_logins: Option<i32>) {
+        *self.state.client_id.lock().unwrap() = client_id;
+        *self.state.max_logins.lock().unwrap() = max_logins;
+        let mut node_id_allocator = self.state.node_id_allocator.lock().unwrap();
+        let new_client_id = client_id.unwrap_or(0);
+        if node_id_allocator.get_client_id() != new_client_id {
+            node_id_allocator.reset(new_client_id);
+        }
+    }
+
+    /// Returns the client ID, which the server assigned on
+    /// [`set_receive_notifications`](#method.set_receive_notifications).
+    pub fn get_client_id(&self) -> Option<i32> {
+        *self.state.client_id.lock().unwrap()
+    }
+
+    /// Returns the maximum number of clients, which the server accepts, received on
+    /// [`set_receive_notifications`](#method.set_receive_notifications).
+    pub fn get_max_logins(&self) -> Option<i32> {
+        *self.state.max_logins.lock().unwrap()
+    }
+
     /// Get status and perform callback with [`ServerStatus`](server/struct.ServerStatus.html) as the parameter.
     /// > status won't return, if the server is in dump_osc mode
     pub fn get_status<F>(&self, on_reply: F) -> ScClientResult<&Self>
@@ -95,20 +799,49 @@ impl Server {
         F: Fn(ServerStatus) + Send + Sync + 'static,
     {
         let status_responder = StatusResponder::new(on_reply);
-        let osc_server = self.osc_server.borrow();
+        let osc_server = &self.osc_server;
         osc_server.add_responder(status_responder)?;
         osc_server.send_message("/status", None)?;
         Ok(self)
     }
 
+    /// Blocks until the status is received.
+    /// > status won't return, if the server is in dump_osc mode
+    pub fn status(&self) -> ScClientResult<ServerStatus> {
+        self.wait_for_reply("/status", None, StatusResponder::new)
+    }
+
+    /// Query the tree of nodes, starting at the group with `group_id`, and perform callback with
+    /// [`NodeTree`](server/enum.NodeTree.html) as the parameter. If `include_controls` is
+    /// `true`, the current control values of synths will be included into the tree.
+    /// > the tree won't return, if the server is in dump_osc mode
+    pub fn query_tree<F>(
+        &self,
+        group_id: i32,
+        include_controls: bool,
+        on_reply: F,
+    ) -> ScClientResult<&Self>
+    where
+        F: Fn(NodeTree) + Send + Sync + 'static,
+    {
+        let query_tree_responder = QueryTreeResponder::new(group_id, on_reply);
+        let osc_server = &self.osc_server;
+        osc_server.add_responder(query_tree_responder)?;
+        osc_server.send_message(
+            "/g_queryTree",
+            Some(vec![group_id.into(), (include_controls as i32).into()]),
+        )?;
+        Ok(self)
+    }
+
     pub fn set_dump_osc_mode(&self, mode: DumpOscMode) -> ScClientResult<&Self> {
-        let osc_server = self.osc_server.borrow();
+        let osc_server = &self.osc_server;
         osc_server.send_message("/dumpOSC", Some(vec![(mode as i32).into()]))?;
         Ok(self)
     }
 
     pub fn clear_message_queue(&self) -> ScClientResult<&Self> {
-        let osc_server = self.osc_server.borrow();
+        let osc_server = &self.osc_server;
         osc_server.send_message("/clearSched", None)?;
         Ok(self)
     }
@@ -120,12 +853,17 @@ impl Server {
         F: Fn(ServerVersion) + Send + Sync + 'static,
     {
         let version_responder = VersionResponder::new(on_reply);
-        let osc_server = self.osc_server.borrow();
+        let osc_server = &self.osc_server;
         osc_server.add_responder(version_responder)?;
         osc_server.send_message("/version", None)?;
         Ok(self)
     }
 
+    /// Blocks until the version is received.
+    pub fn version(&self) -> ScClientResult<ServerVersion> {
+        self.wait_for_reply("/version", None, VersionResponder::new)
+    }
+
     pub fn call_plugin_command(
         &self,
         command_name: &str,
@@ -135,25 +873,69 @@ impl Server {
         if let Some(mut command_args) = arguments {
             send_args.append(&mut command_args);
         };
-        let osc_server = self.osc_server.borrow();
+        let osc_server = &self.osc_server;
         osc_server.send_message("/cmd", Some(send_args))?;
         Ok(self)
     }
 
     pub fn set_error_mode(&self, error_mode: ScServerErrorMode) -> ScClientResult<&Self> {
-        let osc_server = self.osc_server.borrow();
+        let osc_server = &self.osc_server;
         osc_server.send_message("/error", Some(vec![(error_mode as i32).into()]))?;
         Ok(self)
     }
 }
 
-// Drop implemented for Server, because when we try to kill the child process in ScServerProcess
-// in drop, we get an error that the process is already exited
-impl Drop for Server {
+/// Message of [`Server::send_request`](struct.Server.html#method.send_request).
+pub(crate) enum Reply<T> {
+    Value(T),
+    /// the server has replied to the sync, with the failure of the command, if it has failed.
+    Synced(Option<ServerFailure>),
+}
+
+impl<T> Reply<T> {
+    /// Keeps the first value in `value`. Returns the result of the request, when it's synced:
+    /// [`ScClientError::ServerFailure`](enum.ScClientError.html), if the command has failed,
+    /// or [`ScClientError::NoReply`](enum.ScClientError.html), if there's no value.
+    pub fn resolve(self, value: &mut Option<T>) -> Option<ScClientResult<T>> {
+        match self {
+            Reply::Value(reply) => {
+                value.get_or_insert(reply);
+                None
+            }
+            Reply::Synced(Some(failure)) => Some(Err(ScClientError::ServerFailure(failure))),
+            Reply::Synced(None) => Some(value.take().ok_or(ScClientError::NoReply)),
+        }
+    }
+}
+
+/// Bundle, started in the current thread, which is cancelled on drop, so the thread doesn't
+/// keep collecting the commands, if `make_commands` fails or panics. The finished bundle isn't
+/// affected.
+struct BundleGuard<'a> {
+    osc_server: &'a OscServer,
+}
+
+impl Drop for BundleGuard<'_> {
     fn drop(&mut self) {
-        if let Some(ref mut process) = *self.sc_server_process.borrow_mut() {
-            process.kill_child().expect("can't kill SC server");
-        };
+        self.osc_server.cancel_bundle();
+    }
+}
+
+// Drop implemented for ServerState, because when we try to kill the child process in
+// ScServerProcess in drop, we get an error that the process is already exited. The state is
+// dropped with the last handle of the server.
+impl Drop for ServerState {
+    fn drop(&mut self) {
+        // a panic here would abort the program, if the state is dropped while unwinding
+        let process = self
+            .sc_server_process
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(process) = process {
+            if let Err(e) = process.kill_child() {
+                error!("Error kill SuperCollider server: {}", e);
+            }
+        }
     }
 }
 
@@ -188,9 +970,9 @@ pub struct ServerStatus {
     /// peak percent CPU usage for signal processing
     pub peak_cpu: f32,
     /// nominal sample rate
-    pub nom_sample_rate: f32,
+    pub nom_sample_rate: f64,
     /// actual sample rate
-    pub actual_sample_rate: f32,
+    pub actual_sample_rate: f64,
 }
 
 #[derive(Clone, Debug)]
@@ -209,8 +991,21 @@ pub struct ServerVersion {
     pub commit_hash: String,
 }
 
-#[derive(Fail, Debug)]
-pub enum ServerError {
-    #[fail(display = "SuperCollider server is already running")]
-    AlreadyRunning,
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeTree {
+    Group {
+        /// node ID.
+        id: i32,
+        /// child nodes in the order of execution.
+        children: Vec<NodeTree>,
+    },
+    Synth {
+        /// node ID.
+        id: i32,
+        /// name of the synth definition.
+        def_name: String,
+        /// control name or index and its value. Mapped controls have bus names, like "c1" or
+        /// "a2", as values. Empty, if the tree was queried without controls.
+        controls: Vec<NodeValue>,
+    },
 }
@@ -1,19 +1,35 @@
-use crate::{types::OscMessage, AfterCallAction, OscResponder, ScClientResult};
+#[cfg(test)]
+mod tests;
+use crate::{types::OscMessage, types::OscType, AfterCallAction, OscResponder, ScClientResult};
 use log::info;
 
-pub struct NotifyResponder {
+pub struct NotifyResponder<F: Fn(NotifyReply) + Send + Sync + 'static> {
     is_receiving: bool,
+    on_reply_callback: F,
 }
 
-impl NotifyResponder {
-    pub fn new(is_receiving: bool) -> Self {
-        NotifyResponder { is_receiving }
+impl<F: Fn(NotifyReply) + Send + Sync + 'static> NotifyResponder<F> {
+    pub fn new(is_receiving: bool, on_reply_callback: F) -> Self {
+        NotifyResponder {
+            is_receiving,
+            on_reply_callback,
+        }
     }
 }
 
-impl OscResponder for NotifyResponder {
-    fn callback(&self, _message: &OscMessage) -> ScClientResult<()> {
-        Ok(info!("Server notifications set to {}", self.is_receiving))
+impl<F: Fn(NotifyReply) + Send + Sync + 'static> OscResponder for NotifyResponder<F> {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        let args = message.args.clone().unwrap_or_default();
+        info!("Server notifications set to {}", self.is_receiving);
+        let int_at = |index: usize| match args.get(index) {
+            Some(OscType::Int(n)) => Some(*n),
+            _ => None,
+        };
+        (self.on_reply_callback)(NotifyReply {
+            client_id: int_at(1),
+            max_logins: int_at(2),
+        });
+        Ok(())
     }
 
     fn get_address(&self) -> String {
@@ -24,3 +40,10 @@ impl OscResponder for NotifyResponder {
         AfterCallAction::None
     }
 }
+
+/// `/done /notify clientID maxLogins`. Old servers don't send client ID and max logins.
+#[derive(Clone, Debug)]
+pub struct NotifyReply {
+    pub client_id: Option<i32>,
+    pub max_logins: Option<i32>,
+}
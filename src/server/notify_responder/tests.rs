@@ -0,0 +1,31 @@
+use super::*;
+use std::sync::{Arc, Mutex};
+
+fn reply_to(address: &str, args: Vec<OscType>) -> Option<NotifyReply> {
+    let reply = Arc::new(Mutex::new(None));
+    let on_reply_reply = reply.clone();
+    let responder = NotifyResponder::new(true, move |notify_reply| {
+        *on_reply_reply.lock().unwrap() = Some(notify_reply);
+    });
+    let message = OscMessage {
+        addr: address.to_string(),
+        args: Some(args),
+    };
+    responder.callback(&message).unwrap();
+    let reply = reply.lock().unwrap().take();
+    reply
+}
+
+#[test]
+fn parse_done_with_client_id_and_max_logins() {
+    let reply = reply_to("/done", vec!["/notify".into(), 3.into(), 8.into()]).unwrap();
+    assert_eq!(reply.client_id, Some(3));
+    assert_eq!(reply.max_logins, Some(8));
+}
+
+#[test]
+fn parse_done_of_old_server() {
+    let reply = reply_to("/done", vec!["/notify".into()]).unwrap();
+    assert_eq!(reply.client_id, None);
+    assert_eq!(reply.max_logins, None);
+}
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests;
+use crate::{
+    types::NodeValue, types::OscMessage, types::OscType, AfterCallAction, NodeTree, OscResponder,
+    ScClientResult,
+};
+use log::warn;
+use std::slice::Iter;
+use std::sync::Mutex;
+
+pub struct QueryTreeResponder<F: Fn(NodeTree) + Send + Sync + 'static> {
+    on_reply_callback: F,
+    group_id: i32,
+    after_call_action: Mutex<AfterCallAction>,
+}
+
+impl<F: Fn(NodeTree) + Send + Sync + 'static> QueryTreeResponder<F> {
+    pub fn new(group_id: i32, on_reply_callback: F) -> Self {
+        QueryTreeResponder {
+            on_reply_callback,
+            group_id,
+            after_call_action: Mutex::new(AfterCallAction::Reschedule),
+        }
+    }
+}
+
+impl<F: Fn(NodeTree) + Send + Sync + 'static> OscResponder for QueryTreeResponder<F> {
+    fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        if let Some(ref args) = message.args {
+            if args.get(1) != Some(&OscType::Int(self.group_id)) {
+                return Ok(());
+            }
+
+            match parse_reply(args) {
+                Some(tree) => (self.on_reply_callback)(tree),
+                None => warn!("Can't parse /g_queryTree.reply: {:?}", args),
+            }
+            *self.after_call_action.lock().unwrap() = AfterCallAction::None;
+        }
+        Ok(())
+    }
+
+    fn get_address(&self) -> String {
+        String::from("/g_queryTree.reply")
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        (*self.after_call_action.lock().unwrap()).clone()
+    }
+}
+
+/// Parses the arguments of `/g_queryTree.reply` into a tree. Returns `None`, if the
+/// arguments are malformed.
+fn parse_reply(args: &[OscType]) -> Option<NodeTree> {
+    let mut args = args.iter();
+    let with_controls = next_int(&mut args)? == 1;
+    let id = next_int(&mut args)?;
+    let num_of_children = next_int(&mut args)?;
+    parse_group(id, num_of_children, with_controls, &mut args)
+}
+
+fn parse_node(args: &mut Iter<OscType>, with_controls: bool) -> Option<NodeTree> {
+    let id = next_int(args)?;
+    let num_of_children = next_int(args)?;
+    if num_of_children < 0 {
+        return parse_synth(id, with_controls, args);
+    }
+    parse_group(id, num_of_children, with_controls, args)
+}
+
+fn parse_group(
+    id: i32,
+    num_of_children: i32,
+    with_controls: bool,
+    args: &mut Iter<OscType>,
+) -> Option<NodeTree> {
+    let mut children = Vec::new();
+    for _ in 0..num_of_children {
+        children.push(parse_node(args, with_controls)?);
+    }
+    Some(NodeTree::Group { id, children })
+}
+
+fn parse_synth(id: i32, with_controls: bool, args: &mut Iter<OscType>) -> Option<NodeTree> {
+    let def_name = match args.next()? {
+        OscType::String(name) => name.clone(),
+        _ => return None,
+    };
+    let mut controls = Vec::new();
+    if with_controls {
+        let num_of_controls = next_int(args)?;
+        for _ in 0..num_of_controls {
+            let control = args.next()?.clone();
+            let value = args.next()?.clone();
+            controls.push(NodeValue(control, value));
+        }
+    }
+    Some(NodeTree::Synth {
+        id,
+        def_name,
+        controls,
+    })
+}
+
+fn next_int(args: &mut Iter<OscType>) -> Option<i32> {
+    match args.next()? {
+        OscType::Int(n) => Some(*n),
+        _ => None,
+    }
+}
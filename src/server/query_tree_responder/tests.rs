@@ -0,0 +1,74 @@
+use super::*;
+
+#[test]
+fn parse_reply_without_controls() {
+    let args: Vec<OscType> = vec![
+        0.into(),
+        0.into(),
+        2.into(),
+        1000.into(),
+        1.into(),
+        1001.into(),
+        (-1).into(),
+        "default".into(),
+        1002.into(),
+        0.into(),
+    ];
+    assert_eq!(
+        Some(NodeTree::Group {
+            id: 0,
+            children: vec![
+                NodeTree::Group {
+                    id: 1000,
+                    children: vec![NodeTree::Synth {
+                        id: 1001,
+                        def_name: String::from("default"),
+                        controls: vec![],
+                    }],
+                },
+                NodeTree::Group {
+                    id: 1002,
+                    children: vec![],
+                },
+            ],
+        }),
+        parse_reply(&args)
+    );
+}
+
+#[test]
+fn parse_reply_with_controls() {
+    let args: Vec<OscType> = vec![
+        1.into(),
+        1.into(),
+        1.into(),
+        1001.into(),
+        (-1).into(),
+        "default".into(),
+        2.into(),
+        "freq".into(),
+        440.0f32.into(),
+        "amp".into(),
+        "c1".into(),
+    ];
+    assert_eq!(
+        Some(NodeTree::Group {
+            id: 1,
+            children: vec![NodeTree::Synth {
+                id: 1001,
+                def_name: String::from("default"),
+                controls: vec![
+                    NodeValue("freq".into(), 440.0f32.into()),
+                    NodeValue("amp".into(), "c1".into()),
+                ],
+            }],
+        }),
+        parse_reply(&args)
+    );
+}
+
+#[test]
+fn parse_malformed_reply() {
+    let args: Vec<OscType> = vec![0.into(), 0.into(), 2.into(), 1000.into(), 0.into()];
+    assert_eq!(None, parse_reply(&args));
+}
@@ -1,56 +1,170 @@
+#[cfg(test)]
+mod tests;
+use super::server_output::{OutputLine, OutputStream, ServerOutput};
 use crate::*;
-use os_pipe::{pipe, PipeReader, PipeWriter};
+use log::error;
+use os_pipe::{pipe, PipeReader};
 use std::io::{BufRead, BufReader};
 use std::process::{Child, Command, ExitStatus};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the child is checked for exit, while waiting for it to get ready.
+const EXIT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Lines of the output, which mean that the server won't get ready.
+const FAILURE_LINES: [&str; 5] = [
+    "could not initialize audio",
+    "server failed to start",
+    "failed to open udp socket",
+    "failed to open tcp socket",
+    "address already in use",
+];
 
 pub struct ScServerProcess {
     child: Child,
-    pipe_reader: PipeReader,
-    #[allow(dead_code)]
-    pipe_writer: PipeWriter,
 }
 
 impl ScServerProcess {
-    pub fn new(options: &Options) -> ScClientResult<Self> {
-        let (pipe_reader, pipe_writer) = pipe()?;
-        let child = match Command::new(options.path.clone())
+    /// Starts the server and blocks until it prints that it's ready. Fails, if the server
+    /// exits, prints a known failure or doesn't get ready in `boot_timeout`. The output of the
+    /// server is passed to `output`.
+    pub fn new(
+        options: &Options,
+        boot_timeout: Duration,
+        output: &ServerOutput,
+    ) -> ScClientResult<Self> {
+        let (stdout_reader, stdout_writer) = pipe()?;
+        let (stderr_reader, stderr_writer) = pipe()?;
+        // the writers are dropped with the command, so the readers get EOF when the child exits
+        let child = Command::new(options.path.clone())
             .args(options.to_args())
-            .stdout(pipe_writer.try_clone().unwrap())
-            .stderr(pipe_writer.try_clone().unwrap())
+            .stdout(stdout_writer)
+            .stderr(stderr_writer)
             .spawn()
-        {
-            Err(e) => panic!("couldn't start {}: {}", options.path, e),
-            Ok(process) => process,
-        };
-        let process = ScServerProcess {
-            child,
-            pipe_reader,
-            pipe_writer,
-        };
-        process.guess_ready_and_pipe_stdout()?;
+            .map_err(|source| ScClientError::ProcessStart {
+                path: options.path.clone(),
+                source,
+            })?;
+        let mut process = ScServerProcess { child };
+        let (sender, lines) = mpsc::channel();
+        ScServerProcess::pipe_output(stdout_reader, OutputStream::Stdout, output, &sender);
+        ScServerProcess::pipe_output(stderr_reader, OutputStream::Stderr, output, &sender);
+        drop(sender);
+        if let Err(e) = process.wait_for_ready(&lines, boot_timeout) {
+            let _ = process.child.kill();
+            let _ = process.child.wait();
+            return Err(e);
+        }
         Ok(process)
     }
 
-    fn guess_ready_and_pipe_stdout(&self) -> ScClientResult<()> {
-        let pipe_reader = self.pipe_reader.try_clone()?;
-        let current_thread = thread::current();
-
+    /// Passes the lines of the stream to `output`, and to `sender`, until the receiver is
+    /// dropped.
+    fn pipe_output(
+        pipe_reader: PipeReader,
+        stream: OutputStream,
+        output: &ServerOutput,
+        sender: &Sender<String>,
+    ) {
+        let output = output.clone();
+        let sender = sender.clone();
         thread::spawn(move || {
             let mut child_out = BufReader::new(pipe_reader);
             let mut line = String::new();
             loop {
-                child_out.read_line(&mut line).unwrap();
-                print!("{}", line);
-                if line.contains("ready") {
-                    current_thread.unpark()
-                }
                 line.clear();
-                thread::sleep(std::time::Duration::from_millis(1));
+                match child_out.read_line(&mut line) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        let text = line.trim_end().to_string();
+                        output.push(OutputLine {
+                            stream,
+                            text: text.clone(),
+                        });
+                        // the receiver is gone, if the server is ready already
+                        let _ = sender.send(text);
+                    }
+                    Err(e) => {
+                        error!("Error read server output: {}", e);
+                        break;
+                    }
+                }
             }
         });
+    }
 
-        Ok(thread::park())
+    fn wait_for_ready(
+        &mut self,
+        lines: &Receiver<String>,
+        boot_timeout: Duration,
+    ) -> ScClientResult<()> {
+        let deadline = Instant::now() + boot_timeout;
+        let mut output = Vec::new();
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(ScClientError::BootTimeout {
+                    timeout: boot_timeout,
+                    output,
+                });
+            }
+
+            match lines.recv_timeout(EXIT_CHECK_INTERVAL.min(deadline - now)) {
+                Ok(line) => {
+                    let lowercase_line = line.to_lowercase();
+                    let is_failure = FAILURE_LINES.iter().any(|f| lowercase_line.contains(f));
+                    let is_ready = lowercase_line.contains("ready");
+                    output.push(line.clone());
+                    if is_failure {
+                        return Err(ScClientError::BootFailed { line, output });
+                    }
+                    if is_ready {
+                        return Ok(());
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Some(status) = self.child.try_wait()? {
+                        output.extend(lines.try_iter());
+                        return Err(ScClientError::ProcessExited { status, output });
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return match self.wait_for_exit(deadline)? {
+                        Some(status) => Err(ScClientError::ProcessExited { status, output }),
+                        None => Err(ScClientError::BootTimeout {
+                            timeout: boot_timeout,
+                            output,
+                        }),
+                    };
+                }
+            }
+        }
+    }
+
+    /// Waits for the child to exit, but not past `deadline`. The output is closed already, but
+    /// the child can keep running, for example, if it has daemonized.
+    fn wait_for_exit(&mut self, deadline: Instant) -> ScClientResult<Option<ExitStatus>> {
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Ok(Some(status));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            thread::sleep(EXIT_CHECK_INTERVAL.min(deadline - now));
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Returns the exit status, if the server has exited, without blocking.
+    pub fn try_wait(&mut self) -> ScClientResult<Option<ExitStatus>> {
+        Ok(self.child.try_wait()?)
     }
 
     pub fn kill_child(&mut self) -> ScClientResult<()> {
@@ -61,3 +175,16 @@ impl ScServerProcess {
         Ok(self.child.wait()?)
     }
 }
+
+/// Writes the shell script, which stands in for the server in the tests, into the temporary
+/// directory and returns its path. The name should be unique among the tests.
+#[cfg(all(test, unix))]
+pub(crate) fn write_server_script(name: &str, script: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let file_name = format!("sc_client_{}_{}.sh", name, std::process::id());
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
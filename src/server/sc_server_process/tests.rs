@@ -0,0 +1,63 @@
+use super::*;
+
+/// Options, which run `path` with the device name among the arguments, so `echo` prints it.
+fn options(path: &str, device_name: &str) -> Options {
+    let mut options = Options::default();
+    options.path = path.to_string();
+    options.device_name = Some(device_name.to_string());
+    options
+}
+
+#[cfg(unix)]
+#[test]
+fn boot_fails_with_output_when_process_exits() {
+    let timeout = Duration::from_secs(5);
+    match ScServerProcess::new(
+        &options("echo", "no audio device"),
+        timeout,
+        &ServerOutput::new(),
+    ) {
+        Err(ScClientError::ProcessExited { status, output }) => {
+            assert!(status.success());
+            assert!(output[0].contains("no audio device"));
+        }
+        _ => panic!("the exit isn't reported"),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn boot_fails_on_known_failure_line() {
+    let options = options("echo", "ERROR: could not initialize audio.");
+    match ScServerProcess::new(&options, Duration::from_secs(5), &ServerOutput::new()) {
+        Err(ScClientError::BootFailed { line, .. }) => {
+            assert!(line.contains("could not initialize audio"))
+        }
+        _ => panic!("the failure isn't reported"),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn boot_succeeds_when_process_gets_ready() {
+    let options = options("echo", "SuperCollider 3 server ready.");
+    let output = ServerOutput::new();
+    let mut process = ScServerProcess::new(&options, Duration::from_secs(5), &output).unwrap();
+    process.wait_for_finish().unwrap();
+    assert_eq!(output.recent_lines()[0].stream, OutputStream::Stdout);
+}
+
+#[cfg(unix)]
+#[test]
+fn boot_times_out_when_process_closes_output_and_keeps_running() {
+    let path = write_server_script("closed_output", "exec >&- 2>&-\nexec sleep 60");
+    let mut options = Options::default();
+    options.path = path.to_string_lossy().into_owned();
+    let timeout = Duration::from_millis(500);
+    let result = ScServerProcess::new(&options, timeout, &ServerOutput::new());
+    std::fs::remove_file(&path).unwrap();
+    match result {
+        Err(ScClientError::BootTimeout { timeout: t, .. }) => assert_eq!(timeout, t),
+        _ => panic!("the timeout isn't reported"),
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests;
+use log::{log, Level};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+type Subscribers = RwLock<Vec<(usize, OutputCallback)>>;
+type OutputCallback = Arc<dyn Fn(&OutputLine) + Send + Sync>;
+
+/// How many recent lines [`ServerOutput`](struct.ServerOutput.html) keeps by default.
+pub const DEFAULT_OUTPUT_HISTORY_SIZE: usize = 200;
+
+/// Collects the lines, which the server process prints. Each line is forwarded to `log`,
+/// passed to the subscribers and kept in the history of recent lines, so it can be dumped,
+/// when something goes wrong.
+///
+/// By default, the lines of stdout are logged at `Info` level and the lines of stderr at
+/// `Warn` level.
+#[derive(Clone)]
+pub struct ServerOutput {
+    history: Arc<Mutex<VecDeque<OutputLine>>>,
+    history_size: Arc<AtomicUsize>,
+    log_levels: Arc<RwLock<(Option<Level>, Option<Level>)>>,
+    subscribers: Arc<Subscribers>,
+    next_subscription_id: Arc<AtomicUsize>,
+}
+
+impl ServerOutput {
+    pub fn new() -> Self {
+        ServerOutput {
+            history: Arc::new(Mutex::new(VecDeque::new())),
+            history_size: Arc::new(AtomicUsize::new(DEFAULT_OUTPUT_HISTORY_SIZE)),
+            log_levels: Arc::new(RwLock::new((Some(Level::Info), Some(Level::Warn)))),
+            subscribers: Arc::new(RwLock::new(Vec::new())),
+            next_subscription_id: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Calls `callback` on each line of the output. It's called from the thread, which reads
+    /// the output, so it shouldn't block.
+    pub fn subscribe<F>(&self, callback: F) -> usize
+    where
+        F: Fn(&OutputLine) + Send + Sync + 'static,
+    {
+        let id = self.next_subscription_id.fetch_add(1, Ordering::SeqCst);
+        self.subscribers
+            .write()
+            .unwrap()
+            .push((id, Arc::new(callback)));
+        id
+    }
+
+    pub fn unsubscribe(&self, subscription_id: usize) {
+        self.subscribers
+            .write()
+            .unwrap()
+            .retain(|(id, _)| *id != subscription_id);
+    }
+
+    /// Sets the levels, which the lines of stdout and stderr are logged at. `None` turns the
+    /// logging of the stream off.
+    pub fn set_log_levels(&self, stdout: Option<Level>, stderr: Option<Level>) {
+        *self.log_levels.write().unwrap() = (stdout, stderr);
+    }
+
+    /// Sets how many recent lines are kept. The oldest lines are dropped, if there are more.
+    pub fn set_history_size(&self, size: usize) {
+        self.history_size.store(size, Ordering::SeqCst);
+        let mut history = self.history.lock().unwrap();
+        while history.len() > size {
+            history.pop_front();
+        }
+    }
+
+    /// Returns the recent lines, from the oldest to the newest.
+    pub fn recent_lines(&self) -> Vec<OutputLine> {
+        self.history.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.history.lock().unwrap().clear();
+    }
+
+    pub(crate) fn push(&self, line: OutputLine) {
+        let (stdout_level, stderr_level) = *self.log_levels.read().unwrap();
+        let level = match line.stream {
+            OutputStream::Stdout => stdout_level,
+            OutputStream::Stderr => stderr_level,
+        };
+        if let Some(level) = level {
+            log!(level, "{}", line.text);
+        }
+
+        // the callbacks are cloned, so they can subscribe or unsubscribe
+        let callbacks: Vec<OutputCallback> = self
+            .subscribers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in callbacks {
+            callback(&line);
+        }
+
+        let history_size = self.history_size.load(Ordering::SeqCst);
+        let mut history = self.history.lock().unwrap();
+        history.push_back(line);
+        while history.len() > history_size {
+            history.pop_front();
+        }
+    }
+}
+
+impl Default for ServerOutput {
+    fn default() -> Self {
+        ServerOutput::new()
+    }
+}
+
+/// Stream of the server process, which a line was printed to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Line of the server process output without the line break.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub text: String,
+}
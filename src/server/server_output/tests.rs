@@ -0,0 +1,32 @@
+use super::*;
+
+fn line(text: &str) -> OutputLine {
+    OutputLine {
+        stream: OutputStream::Stdout,
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn history_keeps_only_recent_lines() {
+    let output = ServerOutput::new();
+    output.set_history_size(2);
+    for text in &["one", "two", "three"] {
+        output.push(line(text));
+    }
+    assert_eq!(output.recent_lines(), vec![line("two"), line("three")]);
+}
+
+#[test]
+fn subscribers_receive_lines_until_unsubscribed() {
+    let output = ServerOutput::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let subscriber_received = received.clone();
+    let id = output.subscribe(move |line| {
+        subscriber_received.lock().unwrap().push(line.text.clone());
+    });
+    output.push(line("one"));
+    output.unsubscribe(id);
+    output.push(line("two"));
+    assert_eq!(*received.lock().unwrap(), vec!["one".to_string()]);
+}
@@ -1,5 +1,6 @@
 use crate::{
-    types::OscMessage, types::OscType, AfterCallAction, OscResponder, ScClientResult, ServerStatus,
+    types::OscMessage, types::OscType, AfterCallAction, OscResponder, ScClientError,
+    ScClientResult, ServerStatus,
 };
 
 pub struct StatusResponder<F: Fn(ServerStatus) + Send + Sync + 'static> {
@@ -14,44 +15,51 @@ impl<F: Fn(ServerStatus) + Send + Sync + 'static> StatusResponder<F> {
 
 impl<F: Fn(ServerStatus) + Send + Sync + 'static> OscResponder for StatusResponder<F> {
     fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
-        if let Some(ref args) = message.args {
-            let mut server_status = ServerStatus {
-                num_of_ugens: 0,
-                num_of_synths: 0,
-                num_of_groups: 0,
-                num_of_synthdefs: 0,
-                avg_cpu: 0.0,
-                peak_cpu: 0.0,
-                nom_sample_rate: 0.0,
-                actual_sample_rate: 0.0,
-            };
-            if let OscType::Int(n) = args[0] {
-                server_status.num_of_ugens = n;
-            }
-            if let OscType::Int(n) = args[1] {
-                server_status.num_of_synths = n;
-            }
-            if let OscType::Int(n) = args[2] {
-                server_status.num_of_groups = n;
-            }
-            if let OscType::Int(n) = args[3] {
-                server_status.num_of_synthdefs = n;
-            }
-            if let OscType::Float(a) = args[4] {
-                server_status.avg_cpu = a;
-            }
-            if let OscType::Float(p) = args[5] {
-                server_status.peak_cpu = p;
-            }
-            if let OscType::Float(n) = args[6] {
-                server_status.nom_sample_rate = n;
-            }
-            if let OscType::Float(a) = args[7] {
-                server_status.actual_sample_rate = a;
-            }
-
-            (self.on_reply_callback)(server_status);
+        // the reply is `1 ugens synths groups synthdefs avg_cpu peak_cpu nom_sr actual_sr`,
+        // the first argument is unused
+        let args = message.args.as_deref().unwrap_or_default();
+        if args.len() < 9 {
+            return Err(ScClientError::Responder(format!(
+                "Malformed /status.reply: {:?}",
+                args
+            )));
+        }
+        let mut server_status = ServerStatus {
+            num_of_ugens: 0,
+            num_of_synths: 0,
+            num_of_groups: 0,
+            num_of_synthdefs: 0,
+            avg_cpu: 0.0,
+            peak_cpu: 0.0,
+            nom_sample_rate: 0.0,
+            actual_sample_rate: 0.0,
+        };
+        if let Some(OscType::Int(n)) = args.get(1) {
+            server_status.num_of_ugens = *n;
+        }
+        if let Some(OscType::Int(n)) = args.get(2) {
+            server_status.num_of_synths = *n;
+        }
+        if let Some(OscType::Int(n)) = args.get(3) {
+            server_status.num_of_groups = *n;
+        }
+        if let Some(OscType::Int(n)) = args.get(4) {
+            server_status.num_of_synthdefs = *n;
         }
+        if let Some(OscType::Float(a)) = args.get(5) {
+            server_status.avg_cpu = *a;
+        }
+        if let Some(OscType::Float(p)) = args.get(6) {
+            server_status.peak_cpu = *p;
+        }
+        if let Some(OscType::Double(n)) = args.get(7) {
+            server_status.nom_sample_rate = *n;
+        }
+        if let Some(OscType::Double(a)) = args.get(8) {
+            server_status.actual_sample_rate = *a;
+        }
+
+        (self.on_reply_callback)(server_status);
         Ok(())
     }
 
@@ -0,0 +1,114 @@
+use crate::{ScClientResult, Server};
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+type EventCallback = Arc<dyn Fn(&ServerEvent) + Send + Sync>;
+type BootAction = Arc<dyn Fn(&Server) -> ScClientResult<()> + Send + Sync>;
+
+/// How often the server process is checked for exit.
+pub const SUPERVISION_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How many times in a row the server is rebooted by default, if it keeps exiting, for
+/// example, because of a broken boot action or a missing audio device.
+pub const DEFAULT_MAX_REBOOT_ATTEMPTS: usize = 3;
+
+/// How long the server has to run before exiting, so its earlier reboots aren't counted.
+pub const STABLE_RUN_TIME: Duration = Duration::from_secs(60);
+
+/// Events of the server process, which aren't caused by the client.
+#[derive(Clone, Debug)]
+pub enum ServerEvent {
+    /// the server process has exited by itself, for example, it has crashed.
+    Exited(ExitStatus),
+    /// the server was rebooted after it had exited.
+    Rebooted,
+}
+
+/// Keeps the subscribers for [`ServerEvent`](enum.ServerEvent.html) and the actions, which
+/// are performed on each boot.
+#[derive(Clone, Default)]
+pub struct Supervisor {
+    subscribers: Arc<RwLock<Vec<(usize, EventCallback)>>>,
+    boot_actions: Arc<RwLock<Vec<(usize, BootAction)>>>,
+    next_id: Arc<AtomicUsize>,
+    is_auto_reboot: Arc<AtomicBool>,
+}
+
+impl Supervisor {
+    pub fn subscribe<F>(&self, callback: F) -> usize
+    where
+        F: Fn(&ServerEvent) + Send + Sync + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        self.subscribers
+            .write()
+            .unwrap()
+            .push((id, Arc::new(callback)));
+        id
+    }
+
+    pub fn unsubscribe(&self, subscription_id: usize) {
+        self.subscribers
+            .write()
+            .unwrap()
+            .retain(|(id, _)| *id != subscription_id);
+    }
+
+    pub fn add_boot_action<F>(&self, action: F) -> usize
+    where
+        F: Fn(&Server) -> ScClientResult<()> + Send + Sync + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        self.boot_actions
+            .write()
+            .unwrap()
+            .push((id, Arc::new(action)));
+        id
+    }
+
+    pub fn remove_boot_action(&self, action_id: usize) {
+        self.boot_actions
+            .write()
+            .unwrap()
+            .retain(|(id, _)| *id != action_id);
+    }
+
+    pub fn set_auto_reboot(&self, is_auto_reboot: bool) {
+        self.is_auto_reboot.store(is_auto_reboot, Ordering::SeqCst);
+    }
+
+    pub fn is_auto_reboot(&self) -> bool {
+        self.is_auto_reboot.load(Ordering::SeqCst)
+    }
+
+    /// Performs the boot actions in the order they were added. Stops on the first error.
+    pub fn on_boot(&self, server: &Server) -> ScClientResult<()> {
+        // the actions are cloned, so they can add or remove actions
+        let actions: Vec<BootAction> = self
+            .boot_actions
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(_, action)| action.clone())
+            .collect();
+        for action in actions {
+            action(server)?;
+        }
+        Ok(())
+    }
+
+    pub fn emit(&self, event: &ServerEvent) {
+        let callbacks: Vec<EventCallback> = self
+            .subscribers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in callbacks {
+            callback(event);
+        }
+    }
+}
@@ -0,0 +1,471 @@
+use super::*;
+use crate::osc_server::spawn_tcp_server;
+#[cfg(unix)]
+use crate::server::sc_server_process::write_server_script;
+use crate::types::{NodeValue, OscMessage, OscTime};
+use crate::{AddAction, Buffer, ControlBus, Group, Node, Synth};
+use rosc::{decoder, encoder, OscBundle, OscPacket};
+
+fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+
+#[test]
+fn server_and_handles_can_be_shared_between_threads() {
+    assert_shareable::<Server>();
+    assert_shareable::<Synth>();
+    assert_shareable::<Group>();
+    assert_shareable::<Buffer>();
+    assert_shareable::<ControlBus>();
+}
+
+#[cfg(unix)]
+#[test]
+fn supervisor_reports_exit_and_reboots() {
+    // `echo` prints the device name and exits, like a server, which crashes after booting
+    let mut options = Options::default();
+    options.path = String::from("echo");
+    options.device_name = Some(String::from("server ready"));
+    options.udp_port_number = 0;
+    let server = Server::new(options).unwrap();
+
+    let (sender, events) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    server.subscribe_events(move |event| {
+        let _ = sender.lock().unwrap().send(event.clone());
+    });
+    let boot_count = Arc::new(Mutex::new(0));
+    let action_boot_count = boot_count.clone();
+    server.add_boot_action(move |_| {
+        *action_boot_count.lock().unwrap() += 1;
+        Ok(())
+    });
+
+    server.set_auto_reboot(true).boot().unwrap();
+    let timeout = Duration::from_secs(5);
+    match events.recv_timeout(timeout).unwrap() {
+        ServerEvent::Exited(status) => assert!(status.success()),
+        event => panic!("unexpected event: {:?}", event),
+    }
+    match events.recv_timeout(timeout).unwrap() {
+        ServerEvent::Rebooted => {}
+        event => panic!("unexpected event: {:?}", event),
+    }
+    server.set_auto_reboot(false);
+    assert!(*boot_count.lock().unwrap() >= 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn auto_reboot_stops_after_max_attempts() {
+    let mut options = Options::default();
+    options.path = String::from("echo");
+    options.device_name = Some(String::from("server ready"));
+    options.udp_port_number = 0;
+    let server = Server::new(options).unwrap();
+
+    let (sender, events) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    server.subscribe_events(move |event| {
+        let _ = sender.lock().unwrap().send(event.clone());
+    });
+
+    server
+        .set_auto_reboot(true)
+        .set_max_reboot_attempts(1)
+        .boot()
+        .unwrap();
+    let timeout = Duration::from_secs(5);
+    let mut num_of_reboots = 0;
+    let mut num_of_exits = 0;
+    while let Ok(event) = events.recv_timeout(timeout) {
+        match event {
+            ServerEvent::Exited(_) => num_of_exits += 1,
+            ServerEvent::Rebooted => num_of_reboots += 1,
+        }
+        if num_of_exits == 2 {
+            break;
+        }
+    }
+    assert_eq!((2, 1), (num_of_exits, num_of_reboots));
+    assert!(events.recv_timeout(SUPERVISION_INTERVAL * 3).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_boot_action_kills_server() {
+    // the script gets ready and keeps running, like a server
+    let path = write_server_script("failed_boot_action", "echo server ready\nexec sleep 60");
+    let mut options = Options::default();
+    options.path = path.to_string_lossy().into_owned();
+    options.udp_port_number = 0;
+    let server = Server::new(options).unwrap();
+    server.add_boot_action(|_| Err(ScClientError::NoReply));
+
+    let result = server.boot().map(|_| ());
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(ScClientError::NoReply)));
+    assert!(server.state.sc_server_process.lock().unwrap().is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn shutdown_with_auto_reboot_does_not_reboot() {
+    let pid_path = std::env::temp_dir().join(format!("sc_client_pid_{}", std::process::id()));
+    let script = format!(
+        "echo $$ > {}\necho server ready\nexec sleep 60",
+        pid_path.display()
+    );
+    let path = write_server_script("shutdown_with_auto_reboot", &script);
+    // the stand-in kills the process on `/quit`, like the server exits
+    let quit_pid_path = pid_path.clone();
+    let (port, _) = spawn_replying_server(move |message| match message.addr.as_str() {
+        "/quit" => {
+            let pid = std::fs::read_to_string(&quit_pid_path).unwrap();
+            std::process::Command::new("kill")
+                .arg(pid.trim())
+                .status()
+                .unwrap();
+            vec![OscMessage {
+                addr: String::from("/done"),
+                args: Some(vec!["/quit".into()]),
+            }]
+        }
+        "/sync" => vec![synced(message)],
+        _ => vec![],
+    });
+    let mut options = Options::default();
+    options.path = path.to_string_lossy().into_owned();
+    options.udp_port_number = 0;
+    options.tcp_port_number = port;
+    let server = Server::new(options).unwrap();
+    let (sender, events) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    server.subscribe_events(move |event| {
+        let _ = sender.lock().unwrap().send(event.clone());
+    });
+
+    server.set_auto_reboot(true).boot().unwrap();
+    let result = server.shutdown().map(|_| ());
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&pid_path).unwrap();
+    result.unwrap();
+    assert!(events.recv_timeout(SUPERVISION_INTERVAL * 3).is_err());
+    assert!(server.state.sc_server_process.lock().unwrap().is_none());
+}
+
+/// Starts a TCP stand-in of a running server, which answers each received message with the
+/// replies of `reply`. Several replies are sent in a bundle. Returns its port and the receiver
+/// of the addresses of the received messages.
+fn spawn_replying_server<F>(reply: F) -> (u16, mpsc::Receiver<String>)
+where
+    F: Fn(&OscMessage) -> Vec<OscMessage> + Send + 'static,
+{
+    let (sender, addresses) = mpsc::channel();
+    let address = spawn_tcp_server(1, None, move |packet| {
+        let message = match decoder::decode(&packet) {
+            Ok(OscPacket::Message(message)) => message,
+            _ => return None,
+        };
+        let mut replies = reply(&message);
+        // sent before the reply, so it's received, when the client gets the reply
+        let _ = sender.send(message.addr);
+        let reply = match replies.len() {
+            0 => return None,
+            1 => OscPacket::Message(replies.remove(0)),
+            _ => OscPacket::Bundle(OscBundle {
+                timetag: OscTime::IMMEDIATELY.into(),
+                content: replies.into_iter().map(OscPacket::Message).collect(),
+            }),
+        };
+        Some(encoder::encode(&reply).unwrap())
+    });
+    let port = address.rsplit(':').next().unwrap().parse().unwrap();
+    (port, addresses)
+}
+
+/// Starts a TCP stand-in of a running server, which replies to `/status` and `/sync`.
+fn spawn_external_server() -> (u16, mpsc::Receiver<String>) {
+    spawn_replying_server(|message| match message.addr.as_str() {
+        "/status" => vec![status_reply()],
+        "/sync" => vec![synced(message)],
+        _ => vec![],
+    })
+}
+
+/// Creates the reply to `/status` of the server with one synth and one group.
+fn status_reply() -> OscMessage {
+    OscMessage {
+        addr: String::from("/status.reply"),
+        args: Some(vec![
+            1.into(),
+            2.into(),
+            1.into(),
+            1.into(),
+            3.into(),
+            0.5f32.into(),
+            1.5f32.into(),
+            44100.0f64.into(),
+            44099.9f64.into(),
+        ]),
+    }
+}
+
+/// Creates the reply to the `/sync` message.
+fn synced(sync: &OscMessage) -> OscMessage {
+    OscMessage {
+        addr: String::from("/synced"),
+        args: sync.args.clone(),
+    }
+}
+
+/// Creates the server, which talks over TCP to the stand-in on `port`.
+fn connect_to(port: u16) -> Server {
+    let mut options = Options::default();
+    options.udp_port_number = 0;
+    options.tcp_port_number = port;
+    Server::new(options).unwrap()
+}
+
+#[test]
+fn connect_checks_status_and_shutdown_keeps_external_server_running() {
+    let (port, addresses) = spawn_external_server();
+    let server = connect_to(port);
+
+    server.connect().unwrap();
+    assert!(matches!(server.boot(), Err(ScClientError::AlreadyRunning)));
+    server
+        .set_keeping_external_running(true)
+        .shutdown()
+        .unwrap();
+    server.sync().unwrap();
+
+    // the status is synced too, the shutdown sends nothing
+    let addresses: Vec<String> = addresses.try_iter().collect();
+    assert_eq!(addresses, vec!["/status", "/sync", "/sync"]);
+}
+
+#[test]
+fn connect_keeps_other_connects_out_without_blocking_them() {
+    let (port, _) = spawn_replying_server(|_| vec![]);
+    let server = connect_to(port);
+    server.set_sync_timeout(Duration::from_millis(500));
+    let connecting_server = server.clone();
+    let connecting = thread::spawn(move || connecting_server.connect().map(|_| ()));
+    thread::sleep(Duration::from_millis(100));
+
+    let start = Instant::now();
+    assert!(matches!(
+        server.connect(),
+        Err(ScClientError::AlreadyRunning)
+    ));
+    assert!(start.elapsed() < Duration::from_millis(300));
+    let result = connecting.join().unwrap();
+    assert!(matches!(result, Err(ScClientError::ReplyTimeout(_))));
+    // the reservation is rolled back
+    assert!(matches!(
+        server.connect(),
+        Err(ScClientError::ReplyTimeout(_))
+    ));
+}
+
+#[test]
+fn status_waits_for_reply() {
+    let (port, _) = spawn_external_server();
+    let status = connect_to(port).status().unwrap();
+    assert_eq!(2, status.num_of_ugens);
+    assert_eq!(1, status.num_of_synths);
+    assert_eq!(1, status.num_of_groups);
+    assert_eq!(3, status.num_of_synthdefs);
+    assert_eq!(1.5, status.peak_cpu);
+    assert_eq!(44100.0, status.nom_sample_rate);
+    assert_eq!(44099.9, status.actual_sample_rate);
+}
+
+#[test]
+fn status_times_out_without_reply() {
+    let (port, _) = spawn_replying_server(|_| vec![]);
+    let server = connect_to(port);
+    let timeout = Duration::from_millis(100);
+    server.set_sync_timeout(timeout);
+    let num_of_responders = server.osc_server.num_of_responders();
+    let error = server.status().err().unwrap();
+    assert!(matches!(error, ScClientError::ReplyTimeout(t) if t == timeout));
+    assert_eq!(num_of_responders, server.osc_server.num_of_responders());
+}
+
+#[test]
+fn status_reports_failure_of_command() {
+    let (port, _) = spawn_replying_server(|message| match message.addr.as_str() {
+        "/sync" => vec![synced(message)],
+        _ => vec![fail(&message.addr, "not now")],
+    });
+    match connect_to(port).status() {
+        Err(ScClientError::ServerFailure(failure)) => {
+            assert_eq!(failure.command, "/status");
+            assert_eq!(failure.message, "not now");
+        }
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn failure_is_reported_only_to_request_before_it() {
+    // the stand-in replies, when both the requests and their syncs are received, so the
+    // failure of the first request arrives, while both are waiting
+    let requests = Mutex::new((0, Vec::new()));
+    let (port, _) = spawn_replying_server(move |message| {
+        let mut requests = requests.lock().unwrap();
+        match message.addr.as_str() {
+            "/status" => requests.0 += 1,
+            "/sync" => requests.1.push(message.clone()),
+            _ => return vec![],
+        }
+        let (num_of_statuses, syncs) = &*requests;
+        if *num_of_statuses < 2 || syncs.len() < 2 {
+            return vec![];
+        }
+        vec![
+            fail("/status", "not now"),
+            synced(&syncs[0]),
+            status_reply(),
+            synced(&syncs[1]),
+        ]
+    });
+    let server = connect_to(port);
+
+    let requests: Vec<_> = (0..2)
+        .map(|_| {
+            let server = server.clone();
+            thread::spawn(move || server.status())
+        })
+        .collect();
+    let results: Vec<_> = requests.into_iter().map(|r| r.join().unwrap()).collect();
+    let num_of_failures = results
+        .iter()
+        .filter(|result| matches!(result, Err(ScClientError::ServerFailure(_))))
+        .count();
+    let num_of_replies = results.iter().filter(|result| result.is_ok()).count();
+    assert_eq!((1, 1), (num_of_failures, num_of_replies));
+}
+
+#[test]
+fn truncated_status_reply_is_reported_and_listener_keeps_running() {
+    let (port, _) = spawn_replying_server(|message| match message.addr.as_str() {
+        "/status" => vec![OscMessage {
+            addr: String::from("/status.reply"),
+            args: Some(vec![0.into(), 0.into()]),
+        }],
+        "/sync" => vec![synced(message)],
+        _ => vec![],
+    });
+    let server = connect_to(port);
+    let (sender, errors) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    server.set_error_callback(move |e| {
+        let is_responder_error = matches!(e, ScClientError::Responder(_));
+        let _ = sender.lock().unwrap().send(is_responder_error);
+    });
+
+    assert!(matches!(server.status(), Err(ScClientError::NoReply)));
+    assert!(errors.recv_timeout(Duration::from_secs(1)).unwrap());
+    server.sync().unwrap();
+}
+
+#[test]
+fn panic_in_make_bundle_cancels_bundle() {
+    let (port, addresses) = spawn_replying_server(|_| vec![]);
+    let server = connect_to(port);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        server.make_bundle(None, |_| panic!("the commands can't be made"))
+    }));
+    assert!(result.is_err());
+
+    server.osc_server.send_message("/status", None).unwrap();
+    let timeout = Duration::from_secs(5);
+    assert_eq!("/status", addresses.recv_timeout(timeout).unwrap());
+}
+
+/// Creates the reply `/fail command error`.
+fn fail(command: &str, error: &str) -> OscMessage {
+    OscMessage {
+        addr: String::from("/fail"),
+        args: Some(vec![command.into(), error.into()]),
+    }
+}
+
+#[test]
+fn receive_notifications_reports_failure() {
+    let (port, _) = spawn_replying_server(|message| match message.addr.as_str() {
+        "/notify" => vec![fail("/notify", "too many users")],
+        "/sync" => vec![synced(message)],
+        _ => vec![],
+    });
+    match connect_to(port).set_receive_notifications(true) {
+        Err(ScClientError::ServerFailure(failure)) => {
+            assert_eq!(failure.command, "/notify");
+            assert_eq!(failure.message, "too many users");
+        }
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn receive_notifications_ignores_failure_of_other_command() {
+    let (port, _) = spawn_replying_server(|message| match message.addr.as_str() {
+        "/notify" => vec![
+            fail("/s_new", "SynthDef not found"),
+            OscMessage {
+                addr: String::from("/done"),
+                args: Some(vec!["/notify".into(), 3.into(), 8.into()]),
+            },
+        ],
+        "/sync" => vec![synced(message)],
+        _ => vec![],
+    });
+    let server = connect_to(port);
+    let num_of_responders = server.osc_server.num_of_responders();
+    server.set_receive_notifications(true).unwrap();
+    assert_eq!(Some(3), server.get_client_id());
+    assert_eq!(num_of_responders, server.osc_server.num_of_responders());
+}
+
+#[test]
+fn failure_of_n_set_keeps_pending_control_get() {
+    let (port, _) = spawn_replying_server(|message| match message.addr.as_str() {
+        "/s_get" => {
+            let synth_id = message.args.as_ref().unwrap()[0].clone();
+            vec![
+                fail("/n_set", "Node 1000 not found"),
+                OscMessage {
+                    addr: String::from("/n_set"),
+                    args: Some(vec![synth_id, "freq".into(), 440.0f32.into()]),
+                },
+            ]
+        }
+        "/sync" => vec![synced(message)],
+        _ => vec![],
+    });
+    let server = connect_to(port);
+    let synth = Synth::new(&server, "default", &AddAction::Head, 0, &vec![]).unwrap();
+    let values = synth.get(&["freq"]).unwrap();
+    assert_eq!(vec![NodeValue("freq".into(), 440.0f32.into())], values);
+}
+
+#[test]
+fn freed_node_id_is_reused_after_node_ends() {
+    let (port, _) = spawn_replying_server(|_| vec![]);
+    let server = connect_to(port);
+    let synth = Synth::new(&server, "default", &AddAction::Head, 0, &vec![]).unwrap();
+    synth.free().unwrap();
+    assert_ne!(synth.get_id(), server.next_node_id());
+
+    let args = vec![
+        synth.get_id().into(),
+        0.into(),
+        (-1).into(),
+        (-1).into(),
+        0.into(),
+    ];
+    let end = NodeEvent::from_message("/n_end", &args).unwrap();
+    server.node_watcher().on_event(end);
+    assert_eq!(synth.get_id(), server.next_node_id());
+}
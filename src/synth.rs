@@ -1,40 +1,34 @@
 mod control_value_responder;
-use self::control_value_responder::ControlValueResponder;
-use crate::{types::NodeValue, types::OscType, ScClientResult, Server};
-use failure::Fail;
-use uid::Id;
+pub(crate) use self::control_value_responder::ControlValueResponder;
+use crate::{types::NodeValue, types::OscType, Node, ScClientResult, Server};
 
-pub struct Synth<'a> {
+#[derive(Clone)]
+pub struct Synth {
     name: String,
     id: i32,
     target_id: i32,
-    server: &'a Server,
+    server: Server,
 }
 
-impl<'a> Synth<'a> {
+impl Synth {
     pub fn new(
-        server: &'a Server,
+        server: &Server,
         name: &str,
         add_action: &AddAction,
         target_id: i32,
         args: &Vec<NodeValue>,
     ) -> ScClientResult<Self> {
-        let id = Synth::init_id();
+        let id = server.next_node_id();
         let synth = Synth {
             name: name.to_string(),
             id,
             target_id,
-            server,
+            server: server.clone(),
         };
         synth.init_on_server(add_action, args)?;
         Ok(synth)
     }
 
-    fn init_id() -> i32 {
-        let id = Id::<i32>::new();
-        id.get() as i32
-    }
-
     fn init_on_server(&self, add_action: &AddAction, args: &Vec<NodeValue>) -> ScClientResult<()> {
         let mut send_args: Vec<OscType> = vec![
             self.name.clone().into(),
@@ -46,7 +40,6 @@ impl<'a> Synth<'a> {
         send_args.append(&mut flattened_args);
         self.server
             .osc_server
-            .borrow()
             .send_message("/s_new", Some(send_args))?;
 
         Ok(())
@@ -65,26 +58,48 @@ impl<'a> Synth<'a> {
         F: Fn(Vec<NodeValue>) + Send + Sync + 'static,
     {
         let responder = ControlValueResponder::new(self.id, params.clone(), on_reply);
-        self.server
-            .osc_server
-            .borrow_mut()
-            .add_responder(responder)?;
+        self.server.osc_server.add_responder(responder)?;
 
         let mut send_args = vec![OscType::Int(self.id)];
         send_args.append(params);
         self.server
             .osc_server
-            .borrow()
             .send_message("/s_get", Some(send_args))?;
 
         Ok(self)
     }
 
+    /// Blocks until the values of the controls are received, but not longer than
+    /// [the sync timeout](struct.Server.html#method.set_sync_timeout). The controls are names
+    /// or indices.
+    ///
+    /// ```no_run
+    /// # use sc_client::{AddAction, Options, ScClientResult, Server, Synth};
+    /// # fn main() -> ScClientResult<()> {
+    /// # let server = Server::new(Options::default())?;
+    /// let synth = Synth::new(&server, "default", &AddAction::Head, 0, &vec![])?;
+    /// let values = synth.get(&["freq", "amp"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<T>(&self, controls: &[T]) -> ScClientResult<Vec<NodeValue>>
+    where
+        T: Into<OscType> + Clone,
+    {
+        let params: Vec<OscType> = controls.iter().cloned().map(Into::into).collect();
+        let mut send_args = vec![OscType::Int(self.id)];
+        send_args.extend(params.iter().cloned());
+        self.server
+            .wait_for_reply("/s_get", Some(send_args), |on_reply| {
+                ControlValueResponder::new(self.id, params, on_reply)
+            })
+    }
+
     // pub fn get_control_values_range<F>(&self, from: OscType, number_of_params: u32, on_reply: F) -> ScClientResult<&Self>
     // where F: Fn(OscType) + Send + Sync + 'static {
     // let responder = ControlValuesRangeResponder::new(self.id, param.clone(), on_reply);
-    // self.server.osc_server.borrow_mut().add_responder(responder)?;
-    // self.server.osc_server.borrow().send_message("/s_getn",
+    // self.server.osc_server.add_responder(responder)?;
+    // self.server.osc_server.send_message("/s_getn",
     // Some(vec!(
     // OscType::Int(self.id),
     // param,
@@ -106,6 +121,16 @@ impl<'a> Synth<'a> {
     }
 }
 
+impl Node for Synth {
+    fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    fn get_server(&self) -> &Server {
+        &self.server
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum AddAction {
     /// add the new node to the the head of the group specified by the add target ID.
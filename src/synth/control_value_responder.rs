@@ -36,10 +36,15 @@ impl<F: Fn(Vec<NodeValue>) + Send + Sync + 'static> ControlValueResponder<F> {
 
 impl<F: Fn(Vec<NodeValue>) + Send + Sync + 'static> OscResponder for ControlValueResponder<F> {
     fn callback(&self, message: &OscMessage) -> ScClientResult<()> {
+        if message.addr != "/n_set" { return Ok(()); }
         if let Some(ref args) = message.args {
+            // `/n_set nodeID` is followed by the pairs of the controls and their values
+            if args.first() != Some(&OscType::Int(self.synth_id)) || args.len() % 2 == 0 {
+                return Ok(());
+            }
             let args_vec = args[1..].to_vec();
             let node_values = self.args_into_node_values(&args_vec);
-            if args[0] == OscType::Int(self.synth_id) && self.check_params(&node_values) {
+            if self.check_params(&node_values) {
                 (self.on_reply_callback)(node_values);
                 *self.after_call_action
                     .lock()
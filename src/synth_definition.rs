@@ -6,7 +6,6 @@ impl SynthDefinition {
     pub fn send(server: &Server, buf: &Vec<u8>) -> ScClientResult<()> {
         server
             .osc_server
-            .borrow_mut()
             .send_message("/d_recv", vec![buf.clone().into()].into())?;
         Ok(())
     }
@@ -15,7 +14,6 @@ impl SynthDefinition {
     pub fn load(server: &Server, file_path: &str) -> ScClientResult<()> {
         server
             .osc_server
-            .borrow_mut()
             .send_message("/d_load", vec![file_path.into()].into())?;
         Ok(())
     }
@@ -23,7 +21,6 @@ impl SynthDefinition {
     pub fn load_directory(server: &Server, path: &str) -> ScClientResult<()> {
         server
             .osc_server
-            .borrow_mut()
             .send_message("/d_loadDir", vec![path.into()].into())?;
         Ok(())
     }
@@ -31,7 +28,6 @@ impl SynthDefinition {
     pub fn free(server: &Server, name: &str) -> ScClientResult<()> {
         server
             .osc_server
-            .borrow_mut()
             .send_message("/d_free", vec![name.into()].into())?;
         Ok(())
     }
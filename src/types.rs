@@ -1,4 +1,7 @@
+#[cfg(test)]
+mod tests;
 pub use rosc::{OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscType};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct NodeValue(pub OscType, pub OscType);
@@ -36,3 +39,72 @@ impl From<&Vec<OscType>> for NodeValue {
         NodeValue::from(value.clone())
     }
 }
+
+/// A range of consecutive control values starting at the control, which is specified by index or
+/// name. Used by `/n_setn`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeValuesRange(pub OscType, pub Vec<OscType>);
+
+impl From<&NodeValuesRange> for Vec<OscType> {
+    fn from(range: &NodeValuesRange) -> Self {
+        let mut result = vec![range.0.clone(), (range.1.len() as i32).into()];
+        result.extend(range.1.iter().cloned());
+        result
+    }
+}
+
+impl From<(OscType, Vec<OscType>)> for NodeValuesRange {
+    fn from(range_tuple: (OscType, Vec<OscType>)) -> Self {
+        NodeValuesRange(range_tuple.0, range_tuple.1)
+    }
+}
+
+/// OSC time tag in the NTP format: seconds since 1 January 1900 and fractional part of a second
+/// in 1/2^32 units.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OscTime {
+    pub seconds: u32,
+    pub fractional: u32,
+}
+
+impl OscTime {
+    /// The special time tag, which means "execute immediately".
+    pub const IMMEDIATELY: OscTime = OscTime {
+        seconds: 0,
+        fractional: 1,
+    };
+
+    /// Seconds between 1 January 1900 (NTP epoch) and 1 January 1970 (UNIX epoch).
+    const SECONDS_FROM_NTP_TO_UNIX_EPOCH: u64 = 2_208_988_800;
+
+    /// Returns the time tag for the current system time.
+    pub fn now() -> Self {
+        OscTime::from(SystemTime::now())
+    }
+
+    /// Returns the time tag for the current system time plus `delay`.
+    pub fn from_now(delay: Duration) -> Self {
+        OscTime::from(SystemTime::now() + delay)
+    }
+}
+
+impl From<SystemTime> for OscTime {
+    fn from(time: SystemTime) -> Self {
+        let since_unix_epoch = time
+            .duration_since(UNIX_EPOCH)
+            .expect("system time is before UNIX epoch");
+        let seconds = since_unix_epoch.as_secs() + OscTime::SECONDS_FROM_NTP_TO_UNIX_EPOCH;
+        let fractional = (u64::from(since_unix_epoch.subsec_nanos()) << 32) / 1_000_000_000;
+        OscTime {
+            // NTP seconds wrap around in 2036
+            seconds: seconds as u32,
+            fractional: fractional as u32,
+        }
+    }
+}
+
+impl From<OscTime> for OscType {
+    fn from(time: OscTime) -> Self {
+        OscType::Time(time.seconds, time.fractional)
+    }
+}
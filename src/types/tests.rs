@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn osc_time_from_system_time() {
+    assert_eq!(
+        OscTime {
+            seconds: 2_208_988_800,
+            fractional: 0,
+        },
+        OscTime::from(UNIX_EPOCH)
+    );
+    assert_eq!(
+        OscTime {
+            seconds: 2_208_988_801,
+            fractional: 1 << 31,
+        },
+        OscTime::from(UNIX_EPOCH + Duration::from_millis(1500))
+    );
+    assert_eq!(
+        OscType::Time(2_208_988_800, 1 << 30),
+        OscTime::from(UNIX_EPOCH + Duration::from_millis(250)).into()
+    );
+}
+
+#[test]
+fn osc_time_from_now() {
+    let now = OscTime::now();
+    let later = OscTime::from_now(Duration::from_secs(1));
+    assert!(later > now);
+    assert!(later.seconds - now.seconds <= 2);
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests;
+use crate::types::OscType;
+use std::collections::HashMap;
+
+/// Collects the values of `/b_set`, `/b_setn`, `/c_set` or `/c_setn` replies, which can come in
+/// several messages, until the values at all the requested indices are received.
+pub struct ValueCollector {
+    indices: Vec<i32>,
+    is_range: bool,
+    values: HashMap<i32, Option<f32>>,
+    num_of_missing: usize,
+}
+
+impl ValueCollector {
+    /// `is_range` is `true` for the `/x_setn` replies: `start count values...`, repeated.
+    /// Otherwise the replies are `index value`, repeated.
+    pub fn new(indices: Vec<i32>, is_range: bool) -> Self {
+        let values: HashMap<i32, Option<f32>> =
+            indices.iter().map(|&index| (index, None)).collect();
+        ValueCollector {
+            num_of_missing: values.len(),
+            values,
+            indices,
+            is_range,
+        }
+    }
+
+    /// Stores the values of the reply, which `args` don't include the buffer number.
+    pub fn collect(&mut self, args: &[OscType]) {
+        if !self.is_range {
+            for pair in args.chunks(2) {
+                if let [OscType::Int(index), value] = pair {
+                    self.insert(*index, value);
+                }
+            }
+            return;
+        }
+
+        let mut args = args;
+        while let [OscType::Int(start), OscType::Int(count), rest @ ..] = args {
+            let count = (*count).max(0) as usize;
+            let count = count.min(rest.len());
+            for (offset, value) in rest[..count].iter().enumerate() {
+                self.insert(start + offset as i32, value);
+            }
+            args = &rest[count..];
+        }
+    }
+
+    /// Returns the values in the order of the requested indices, if all of them are received.
+    pub fn values(&self) -> Option<Vec<f32>> {
+        if self.num_of_missing > 0 {
+            return None;
+        }
+        self.indices
+            .iter()
+            .map(|index| self.values.get(index).cloned().flatten())
+            .collect()
+    }
+
+    /// Stores the value, if the index was requested.
+    fn insert(&mut self, index: i32, value: &OscType) {
+        if let (OscType::Float(value), Some(slot)) = (value, self.values.get_mut(&index)) {
+            if slot.is_none() {
+                self.num_of_missing -= 1;
+            }
+            *slot = Some(*value);
+        }
+    }
+}
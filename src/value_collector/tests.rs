@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn collect_values_in_requested_order() {
+    let mut collector = ValueCollector::new(vec![7, 2], false);
+    collector.collect(&[2.into(), 0.2f32.into()]);
+    assert_eq!(None, collector.values());
+    // not requested, so ignored
+    collector.collect(&[5.into(), 0.5f32.into()]);
+    collector.collect(&[7.into(), 0.7f32.into()]);
+    assert_eq!(Some(vec![0.7, 0.2]), collector.values());
+}
+
+#[test]
+fn collect_range_values_from_several_replies() {
+    let mut collector = ValueCollector::new((10..15).collect(), true);
+    collector.collect(&[13.into(), 2.into(), 0.4f32.into(), 0.5f32.into()]);
+    assert_eq!(None, collector.values());
+    collector.collect(&[
+        10.into(),
+        3.into(),
+        0.1f32.into(),
+        0.2f32.into(),
+        0.3f32.into(),
+    ]);
+    assert_eq!(Some(vec![0.1, 0.2, 0.3, 0.4, 0.5]), collector.values());
+}
+
+#[test]
+fn ignore_truncated_range() {
+    let mut collector = ValueCollector::new(vec![0, 1], true);
+    collector.collect(&[0.into(), 2.into(), 0.1f32.into()]);
+    collector.collect(&[0.into(), (-1).into(), 0.1f32.into()]);
+    assert_eq!(None, collector.values());
+}
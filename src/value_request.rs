@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests;
+use crate::{types::OscType, OscResponder, OscServer, ScClientError, ScClientResult};
+use std::ops::Range;
+
+/// Adds the responder, which `make_responder` makes with `on_reply`, and sends `command` with
+/// the arguments of each message. The values can be requested with several messages, because
+/// the chunk size is limited by the size of the reply. If there are no messages,
+/// `on_reply` is called with no values right away, because the server wouldn't reply, so the
+/// responder would never be removed.
+pub fn request_values<T, F, R>(
+    osc_server: &OscServer,
+    command: &str,
+    messages: Vec<Vec<OscType>>,
+    on_reply: F,
+    make_responder: impl FnOnce(F) -> R,
+) -> ScClientResult<()>
+where
+    F: Fn(Vec<T>),
+    R: OscResponder,
+{
+    if messages.is_empty() {
+        on_reply(Vec::new());
+        return Ok(());
+    }
+
+    osc_server.add_responder(make_responder(on_reply))?;
+    for args in messages {
+        osc_server.send_message(command, Some(args))?;
+    }
+    Ok(())
+}
+
+/// Returns the arguments of the messages, which request the values at `indices`, at most
+/// `chunk_size` per message. Each message starts with `prefix`.
+pub fn index_messages(prefix: &[OscType], indices: &[i32], chunk_size: usize) -> Vec<Vec<OscType>> {
+    indices
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let mut args = prefix.to_vec();
+            args.extend(chunk.iter().map(|&index| OscType::Int(index)));
+            args
+        })
+        .collect()
+}
+
+/// Returns the arguments of the messages, which request the values in `range` as
+/// `start count`, at most `chunk_size` per message. Each message starts with `prefix`.
+pub fn range_messages(
+    prefix: &[OscType],
+    range: Range<i32>,
+    chunk_size: usize,
+) -> Vec<Vec<OscType>> {
+    let end = range.end;
+    range
+        .step_by(chunk_size)
+        .map(|start| {
+            let mut args = prefix.to_vec();
+            args.extend(vec![
+                start.into(),
+                (end - start).min(chunk_size as i32).into(),
+            ]);
+            args
+        })
+        .collect()
+}
+
+/// Returns the range of `count` sequential indices, starting at `start`. It's empty, if
+/// `count` isn't positive. Fails, if the range doesn't fit into `i32`.
+pub fn checked_range(start: i32, count: i32) -> ScClientResult<Range<i32>> {
+    let end = start
+        .checked_add(count.max(0))
+        .ok_or(ScClientError::InvalidRange { start, count })?;
+    Ok(start..end)
+}
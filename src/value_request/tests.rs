@@ -0,0 +1,62 @@
+use super::*;
+use crate::{types::OscMessage, AfterCallAction};
+use std::sync::{Arc, Mutex};
+
+struct NeverCalledResponder;
+
+impl OscResponder for NeverCalledResponder {
+    fn callback(&self, _message: &OscMessage) -> ScClientResult<()> {
+        Ok(())
+    }
+
+    fn get_after_call_action(&self, _message: &OscMessage) -> AfterCallAction {
+        AfterCallAction::None
+    }
+
+    fn get_address(&self) -> String {
+        String::from("/c_set")
+    }
+}
+
+#[test]
+fn request_nothing_calls_back_without_responder() {
+    let osc_server = OscServer::new("127.0.0.1:0", "127.0.0.1:57110").unwrap();
+    let num_of_responders = osc_server.num_of_responders();
+    let replies = Arc::new(Mutex::new(Vec::new()));
+    let on_reply = {
+        let replies = replies.clone();
+        move |values: Vec<f32>| replies.lock().unwrap().push(values)
+    };
+
+    request_values(&osc_server, "/c_get", Vec::new(), on_reply, |_| {
+        NeverCalledResponder
+    })
+    .unwrap();
+    assert_eq!(vec![Vec::<f32>::new()], *replies.lock().unwrap());
+    assert_eq!(num_of_responders, osc_server.num_of_responders());
+}
+
+#[test]
+fn split_range_into_chunks() {
+    let prefix = [OscType::Int(3)];
+    let messages = range_messages(&prefix, checked_range(10, 5).unwrap(), 2);
+    let expected: Vec<Vec<OscType>> = vec![
+        vec![3.into(), 10.into(), 2.into()],
+        vec![3.into(), 12.into(), 2.into()],
+        vec![3.into(), 14.into(), 1.into()],
+    ];
+    assert_eq!(expected, messages);
+    assert!(range_messages(&prefix, checked_range(10, -1).unwrap(), 2).is_empty());
+}
+
+#[test]
+fn reject_overflowing_range() {
+    assert!(matches!(
+        checked_range(i32::MAX - 1, 2),
+        Err(ScClientError::InvalidRange { .. })
+    ));
+    assert_eq!(
+        i32::MAX - 1..i32::MAX,
+        checked_range(i32::MAX - 1, 1).unwrap()
+    );
+}